@@ -1,7 +1,8 @@
 use core::hint::black_box;
 use std::path::Path;
 
-use array_range_query::LazySegTreeAddSum;
+use array_range_query::helpers::LazySegTreeAddSumSpec;
+use array_range_query::{CopyLazySegTree, LazySegTree, LazySegTreeAddSum, LazySegTreeSpec};
 
 use criterion::{criterion_group, criterion_main, Criterion};
 mod rng;
@@ -66,6 +67,10 @@ fn bench_range_query(c: &mut Criterion) {
         )
     });
 
+    c.bench_function("lazy_seg_tree_full_range_query_1000", |b| {
+        b.iter(|| black_box(tree.query(..)))
+    });
+
     let window = 750usize;
     assert!(window <= SIZE);
 
@@ -135,6 +140,96 @@ fn bench_range_update(c: &mut Criterion) {
     });
 }
 
+/// Compares `CopyLazySegTree` against the `RefCell`-based tree on the same
+/// interleaved updates and queries.
+fn bench_copy_vs_refcell(c: &mut Criterion) {
+    let values: Vec<i64> = (1..=SIZE as i64).collect();
+    let mut refcell_tree = LazySegTreeAddSum::<i64>::from_vec(values.clone());
+    let mut copy_tree = CopyLazySegTree::<LazySegTreeAddSumSpec<i64>>::from_vec(values);
+
+    let ops = |seed: u64| {
+        let mut rng = rng::Lcg::new(seed);
+        move || {
+            let a = rng.next_usize(SIZE);
+            let bidx = rng.next_usize(SIZE);
+            let (left, right) = if a <= bidx { (a, bidx) } else { (bidx, a) };
+            let val = (rng.next_u64() % 1000) as i64 - 500;
+            (left, right, val)
+        }
+    };
+
+    let mut next = ops(0x0BAD_C0DE);
+    c.bench_function("lazy_seg_tree_refcell_update_query_1000", |b| {
+        b.iter_batched(
+            &mut next,
+            |(left, right, val)| {
+                refcell_tree.update(left..=right, val);
+                black_box(refcell_tree.query(left / 2..=right));
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    let mut next = ops(0x0BAD_C0DE);
+    c.bench_function("lazy_seg_tree_copy_update_query_1000", |b| {
+        b.iter_batched(
+            &mut next,
+            |(left, right, val)| {
+                copy_tree.update(left..=right, val);
+                black_box(copy_tree.query(left / 2..=right));
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+}
+
+/// Range add with a heap-allocated update, so every clone of `U` allocates.
+struct HeavyAddSum;
+
+impl LazySegTreeSpec for HeavyAddSum {
+    type T = i64;
+    /// Per-term increments; the element is increased by their sum.
+    type U = Vec<i64>;
+    const ID: Self::T = 0;
+
+    fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+        *d1 += *d2;
+    }
+
+    fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+        for (a, b) in u1.iter_mut().zip(u2) {
+            *a += *b;
+        }
+    }
+
+    fn op_update_on_data(u: &Self::U, d: &mut Self::T, size: usize) {
+        *d += u.iter().sum::<i64>() * size as i64;
+    }
+}
+
+/// Range updates whose update value is expensive to clone.
+fn bench_heavy_update(c: &mut Criterion) {
+    let values: Vec<i64> = (1..=SIZE as i64).collect();
+    let mut tree = LazySegTree::<HeavyAddSum>::from_vec(values);
+    let mut rng = rng::Lcg::new(0x00DD_BA11);
+
+    c.bench_function("lazy_seg_tree_heavy_update_1000", |b| {
+        b.iter_batched(
+            || {
+                let a = rng.next_usize(SIZE);
+                let bidx = rng.next_usize(SIZE);
+                let (left, right) = if a <= bidx { (a, bidx) } else { (bidx, a) };
+                (left, right, vec![1i64; 64])
+            },
+            |(left, right, val)| {
+                tree.update(left..=right, val);
+                black_box(&tree);
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+}
+
 fn criterion_config() -> Criterion {
     Criterion::default().output_directory(Path::new("target/criterion/lazy_seg_tree_1000"))
 }
@@ -144,6 +239,8 @@ criterion_group! {
     config = criterion_config();
     targets = bench_constructors,
               bench_range_query,
-              bench_range_update
+              bench_range_update,
+              bench_copy_vs_refcell,
+              bench_heavy_update
 }
 criterion_main!(benches);
@@ -0,0 +1,88 @@
+// Query and update throughput of `NarySegTree` against the binary `SegTree`.
+use core::hint::black_box;
+use std::path::Path;
+
+use array_range_query::helpers::SegTreeSumSpec;
+use array_range_query::{NarySegTree, SegTreeSum};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+mod rng;
+
+/// Size used for the benchmarks.
+const SIZE: usize = 1_000_000;
+
+/// Draws a random non-empty range `[left, right)`.
+fn random_range(rng: &mut rng::Lcg) -> (usize, usize) {
+    let a = rng.next_usize(SIZE);
+    let b = rng.next_usize(SIZE);
+    (a.min(b), a.max(b) + 1)
+}
+
+fn bench_query(c: &mut Criterion) {
+    let values: Vec<i64> = (1..=SIZE as i64).collect();
+
+    let binary = SegTreeSum::<i64>::from_slice(&values);
+    let mut rng = rng::Lcg::new(0xC0FFEE);
+    c.bench_function("binary_seg_tree_random_query_1e6", |b| {
+        b.iter_batched(
+            || random_range(&mut rng),
+            |(left, right)| black_box(binary.query(left..right)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let quaternary = NarySegTree::<SegTreeSumSpec<i64>, 4>::from_slice(&values);
+    let mut rng = rng::Lcg::new(0xC0FFEE);
+    c.bench_function("nary_4_seg_tree_random_query_1e6", |b| {
+        b.iter_batched(
+            || random_range(&mut rng),
+            |(left, right)| black_box(quaternary.query(left..right)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let octonary = NarySegTree::<SegTreeSumSpec<i64>, 8>::from_slice(&values);
+    let mut rng = rng::Lcg::new(0xC0FFEE);
+    c.bench_function("nary_8_seg_tree_random_query_1e6", |b| {
+        b.iter_batched(
+            || random_range(&mut rng),
+            |(left, right)| black_box(octonary.query(left..right)),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_update(c: &mut Criterion) {
+    let values: Vec<i64> = (1..=SIZE as i64).collect();
+
+    let mut binary = SegTreeSum::<i64>::from_slice(&values);
+    let mut rng = rng::Lcg::new(0xFEED_FACE);
+    c.bench_function("binary_seg_tree_random_update_1e6", |b| {
+        b.iter_batched(
+            || (rng.next_usize(SIZE), rng.next_u64() as i64),
+            |(index, value)| binary.update(index, value),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let mut quaternary = NarySegTree::<SegTreeSumSpec<i64>, 4>::from_slice(&values);
+    let mut rng = rng::Lcg::new(0xFEED_FACE);
+    c.bench_function("nary_4_seg_tree_random_update_1e6", |b| {
+        b.iter_batched(
+            || (rng.next_usize(SIZE), rng.next_u64() as i64),
+            |(index, value)| quaternary.update(index, value),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn criterion_config() -> Criterion {
+    Criterion::default().output_directory(Path::new("target/criterion/nary_seg_tree"))
+}
+
+criterion_group! {
+    name = benches;
+    config = criterion_config();
+    targets = bench_query, bench_update
+}
+criterion_main!(benches);
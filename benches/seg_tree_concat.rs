@@ -0,0 +1,135 @@
+// Byte-string concatenation, counting how often values are cloned.
+use core::hint::black_box;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use array_range_query::{SegTree, SegTreeSpec};
+
+/// Size used for the benchmarks.
+const SIZE: usize = 1000;
+
+mod rng;
+
+/// Number of `Bytes` clones performed so far.
+static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+/// A byte string whose clones are counted.
+struct Bytes(Vec<u8>);
+
+impl Clone for Bytes {
+    fn clone(&self) -> Self {
+        CLONES.fetch_add(1, Ordering::Relaxed);
+        Bytes(self.0.clone())
+    }
+}
+
+fn concat(a: &mut Bytes, b: &Bytes) {
+    if a.0.is_empty() {
+        *a = b.clone();
+    } else {
+        a.0.extend_from_slice(&b.0);
+    }
+}
+
+/// Concatenation relying on the default `op_owned`.
+struct ConcatSpec;
+impl SegTreeSpec for ConcatSpec {
+    type T = Bytes;
+
+    fn id() -> Self::T {
+        Bytes(Vec::new())
+    }
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        concat(a, b);
+    }
+}
+
+/// Concatenation that moves `b` instead of cloning it when `a` is empty.
+struct OwnedConcatSpec;
+impl SegTreeSpec for OwnedConcatSpec {
+    type T = Bytes;
+
+    fn id() -> Self::T {
+        Bytes(Vec::new())
+    }
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        concat(a, b);
+    }
+
+    fn op_owned(mut a: Self::T, b: Self::T) -> Self::T {
+        if a.0.is_empty() {
+            b
+        } else {
+            a.0.extend_from_slice(&b.0);
+            a
+        }
+    }
+}
+
+fn random_leaves() -> Vec<Bytes> {
+    // Sparse input: about one leaf in eight is non-empty.
+    let mut rng = rng::Lcg::new(0xBEEF);
+    (0..SIZE)
+        .map(|i| match rng.next_usize(8) {
+            0 => Bytes(vec![i as u8; 4]),
+            _ => Bytes(Vec::new()),
+        })
+        .collect()
+}
+
+fn random_ranges(count: usize) -> Vec<(usize, usize)> {
+    let mut rng = rng::Lcg::new(0xC0FFEE);
+    (0..count)
+        .map(|_| {
+            let left = rng.next_usize(SIZE);
+            let right = rng.next_usize(SIZE);
+            (left.min(right), left.max(right))
+        })
+        .collect()
+}
+
+/// Runs every query once and returns how many clones it took.
+fn count_clones<S: SegTreeSpec<T = Bytes>>(tree: &SegTree<S>, ranges: &[(usize, usize)]) -> usize {
+    CLONES.store(0, Ordering::Relaxed);
+    for &(l, r) in ranges {
+        black_box(tree.query(l..r));
+    }
+    CLONES.load(Ordering::Relaxed)
+}
+
+fn bench_concat_query(c: &mut Criterion) {
+    let default_tree = SegTree::<ConcatSpec>::from_vec(random_leaves());
+    let owned_tree = SegTree::<OwnedConcatSpec>::from_vec(random_leaves());
+    let ranges = random_ranges(1000);
+
+    for &(l, r) in &ranges {
+        assert_eq!(default_tree.query(l..r).0, owned_tree.query(l..r).0);
+    }
+    println!(
+        "concat queries: {} clones with default op_owned, {} with moving op_owned",
+        count_clones(&default_tree, &ranges),
+        count_clones(&owned_tree, &ranges)
+    );
+
+    c.bench_function("seg_tree_concat_random_query_1000", |b| {
+        b.iter(|| {
+            for &(l, r) in &ranges {
+                black_box(default_tree.query(l..r));
+            }
+        })
+    });
+
+    c.bench_function("seg_tree_concat_owned_random_query_1000", |b| {
+        b.iter(|| {
+            for &(l, r) in &ranges {
+                black_box(owned_tree.query(l..r));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_concat_query);
+criterion_main!(benches);
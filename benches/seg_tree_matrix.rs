@@ -0,0 +1,87 @@
+// Range products of 3x3 matrices, where every `op` is a full matrix multiply.
+use core::hint::black_box;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use array_range_query::helpers::SegTreeMatrixSpec;
+use array_range_query::{SegTree, SegTreeSpec};
+
+/// Size used for the benchmarks.
+const SIZE: usize = 1000;
+const MOD: i64 = 1_000_000_007;
+
+mod rng;
+
+type Inner = SegTreeMatrixSpec<3, MOD>;
+
+/// Number of matrix multiplications performed so far.
+static MULTIPLICATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the matrix spec to count multiplications.
+struct CountingMatrixSpec;
+impl SegTreeSpec for CountingMatrixSpec {
+    type T = <Inner as SegTreeSpec>::T;
+    const ID: Self::T = Inner::ID;
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        MULTIPLICATIONS.fetch_add(1, Ordering::Relaxed);
+        Inner::op(a, b);
+    }
+}
+
+fn random_ranges(count: usize) -> Vec<(usize, usize)> {
+    let mut rng = rng::Lcg::new(0xC0FFEE);
+    (0..count)
+        .map(|_| {
+            let left = rng.next_usize(SIZE);
+            let right = rng.next_usize(SIZE);
+            (left.min(right), left.max(right))
+        })
+        .collect()
+}
+
+fn bench_matrix_query(c: &mut Criterion) {
+    let mut rng = rng::Lcg::new(0xBEEF);
+    let values: Vec<_> = (0..SIZE)
+        .map(|_| core::array::from_fn(|_| core::array::from_fn(|_| rng.next_usize(10) as i64)))
+        .collect();
+    let tree = SegTree::<CountingMatrixSpec>::from_vec(values);
+    let ranges = random_ranges(1000);
+
+    // Report how many multiplications the queries cost, next to the number of
+    // canonical nodes that must be combined at minimum.
+    MULTIPLICATIONS.store(0, Ordering::Relaxed);
+    for &(l, r) in &ranges {
+        black_box(tree.query(l..r));
+    }
+    let nodes: usize = ranges
+        .iter()
+        .map(|&(l, r)| tree.canonical_nodes(l..r).len().saturating_sub(1))
+        .sum();
+    println!(
+        "matrix queries: {} multiplications for {} queries ({} node merges)",
+        MULTIPLICATIONS.load(Ordering::Relaxed),
+        ranges.len(),
+        nodes
+    );
+
+    c.bench_function("seg_tree_matrix_random_query_1000", |b| {
+        b.iter(|| {
+            for &(l, r) in &ranges {
+                black_box(tree.query(l..r));
+            }
+        })
+    });
+
+    c.bench_function("seg_tree_matrix_single_node_query_1000", |b| {
+        b.iter(|| {
+            for i in 0..SIZE {
+                black_box(tree.query(i..i + 1));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_matrix_query);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+// Compares the serial and rayon-parallel bottom-up builds on a large tree.
+// Run with `cargo bench --features rayon --bench seg_tree_parallel_build`.
+use core::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use array_range_query::SegTreeSum;
+
+/// Size used for the benchmarks.
+const SIZE: usize = 10_000_000;
+
+fn bench_build(c: &mut Criterion) {
+    let values: Vec<i64> = (1..=SIZE as i64).collect();
+
+    let mut group = c.benchmark_group("seg_tree_build_10m");
+    group.sample_size(10);
+
+    group.bench_function("from_vec", |b| {
+        b.iter_batched(
+            || values.clone(), // cloned outside the timed closure
+            |v| {
+                let tree = SegTreeSum::<i64>::from_vec(v);
+                black_box(&tree);
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("from_vec_parallel", |b| {
+        b.iter_batched(
+            || values.clone(),
+            |v| {
+                let tree = SegTreeSum::<i64>::from_vec_parallel(v);
+                black_box(&tree);
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_build);
+criterion_main!(benches);
@@ -0,0 +1,313 @@
+//! Lazy segment tree without interior mutability for `Copy` specs.
+//!
+//! [`LazySegTree`](crate::LazySegTree) keeps its nodes in `RefCell`s so that
+//! `query` can push tags through `&self`, which costs a borrow check per node
+//! access and makes the tree `!Sync`. When both the data type `T` and the
+//! update type `U` are `Copy`, [`CopyLazySegTree`] stores the nodes directly
+//! and pushes tags through `&mut self` instead. Both trees run the same node
+//! logic over their arrays, so results match for any spec.
+
+use crate::lazy_nodes::{self, LazyNodes};
+use crate::{utils, LazySegTreeSpec};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
+
+/// Lazy segment tree for specs whose data and update types are `Copy`.
+///
+/// # Example
+///
+/// ```rust
+/// use array_range_query::helpers::LazySegTreeAddSumSpec;
+/// use array_range_query::CopyLazySegTree;
+///
+/// let mut tree = CopyLazySegTree::<LazySegTreeAddSumSpec<i64>>::from_vec(vec![1, 2, 3, 4, 5]);
+/// assert_eq!(tree.query(1..4), 9);
+/// tree.update(1..4, 10);
+/// assert_eq!(tree.query(..), 45);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CopyLazySegTree<Spec: LazySegTreeSpec>
+where
+    Spec::T: Copy,
+    Spec::U: Copy,
+{
+    size: usize,
+    max_size: usize,
+    max_depth: u32,
+    data: Box<[Spec::T]>,
+    tags: Box<[Option<Spec::U>]>,
+    _spec: PhantomData<Spec>,
+}
+
+impl<Spec: LazySegTreeSpec> CopyLazySegTree<Spec>
+where
+    Spec::T: Copy,
+    Spec::U: Copy,
+{
+    // ===== CONSTRUCTORS =====
+
+    /// Creates a new tree with all values initialized to `Spec::ID`.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn new(size: usize) -> Self {
+        Self::from_leaves(size, vec![Spec::ID; size.next_power_of_two() * 2])
+    }
+
+    /// Creates a new tree from a slice of values.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_slice(values: &[Spec::T]) -> Self {
+        let size = values.len();
+        let max_size = size.next_power_of_two();
+        let mut data = vec![Spec::ID; max_size * 2];
+        data[max_size..max_size + size].copy_from_slice(values);
+        Self::from_leaves(size, data)
+    }
+
+    /// Creates a new tree from a vector of values.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_vec(values: Vec<Spec::T>) -> Self {
+        Self::from_slice(&values)
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Returns the number of elements in the tree.
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the tree holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns the number of leaves in the internal layout, i.e. the next
+    /// power of two of [`len`](Self::len).
+    pub fn capacity(&self) -> usize {
+        self.max_size
+    }
+
+    /// Queries the aggregated value over the given range, pushing pending
+    /// tags on the boundary paths.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::helpers::LazySegTreeAddMaxSpec;
+    /// use array_range_query::CopyLazySegTree;
+    ///
+    /// let mut tree = CopyLazySegTree::<LazySegTreeAddMaxSpec<i32>>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// tree.update(..2, 10);
+    /// assert_eq!(tree.query(0..=4), 12);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&mut self, range: R) -> Spec::T {
+        let (left_inp, right_inp) = utils::parse_range(range, self.size);
+        utils::validate_range(left_inp, right_inp, self.size);
+        if left_inp == right_inp {
+            return Spec::ID;
+        }
+
+        let (l, r) = (self.max_size + left_inp, self.max_size + right_inp);
+        self.nodes_mut().query(l, r)
+    }
+
+    /// Applies an update to all elements in the given range.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::helpers::LazySegTreeAddSumSpec;
+    /// use array_range_query::CopyLazySegTree;
+    ///
+    /// let mut tree = CopyLazySegTree::<LazySegTreeAddSumSpec<i32>>::new(4);
+    /// tree.update(1..=2, 5);
+    /// assert_eq!(tree.query(..), 10);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn update<R: RangeBounds<usize>>(&mut self, range: R, value: Spec::U) {
+        let (left_inp, right_inp) = utils::parse_range(range, self.size);
+        utils::validate_range(left_inp, right_inp, self.size);
+        if left_inp == right_inp || Spec::is_identity_update(&value) {
+            return;
+        }
+
+        let (l, r) = (self.max_size + left_inp, self.max_size + right_inp);
+        self.nodes_mut().update(l, r, value);
+    }
+
+    // ===== PRIVATE HELPER METHODS =====
+
+    /// Wraps a full node array whose leaves are filled, building every
+    /// internal node.
+    fn from_leaves(size: usize, mut data: Vec<Spec::T>) -> Self {
+        let max_size = data.len() / 2;
+        lazy_nodes::build::<Spec>(&mut data);
+
+        Self {
+            size,
+            max_size,
+            max_depth: max_size.trailing_zeros(),
+            data: data.into_boxed_slice(),
+            tags: vec![None; max_size * 2].into_boxed_slice(),
+            _spec: PhantomData,
+        }
+    }
+
+    /// Borrows the node arrays as a [`LazyNodes`] view.
+    fn nodes_mut(&mut self) -> LazyNodes<'_, Spec> {
+        LazyNodes::new(&mut self.data, &mut self.tags, self.max_depth)
+    }
+}
+
+// ===== TESTS =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{LazySegTreeAddMaxSpec, LazySegTreeAddSumSpec};
+    use crate::LazySegTree;
+
+    /// Drives both trees through the same pseudo-random operations.
+    fn check_matches_lazy<Spec>(values: &[Spec::T], mut next_update: impl FnMut(u64) -> Spec::U)
+    where
+        Spec: LazySegTreeSpec,
+        Spec::T: Copy + PartialEq + core::fmt::Debug,
+        Spec::U: Copy,
+    {
+        let mut copy_tree = CopyLazySegTree::<Spec>::from_slice(values);
+        let mut lazy_tree = LazySegTree::<Spec>::from_slice(values);
+        let n = values.len() as u64;
+
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        for _ in 0..500 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let a = ((state >> 33) % (n + 1)) as usize;
+            let b = ((state >> 17) % (n + 1)) as usize;
+            let (l, r) = (a.min(b), a.max(b));
+            if state & 1 == 0 {
+                let u = next_update(state >> 40);
+                copy_tree.update(l..r, u);
+                lazy_tree.update(l..r, u);
+            } else {
+                assert_eq!(copy_tree.query(l..r), lazy_tree.query(l..r));
+            }
+        }
+
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                assert_eq!(copy_tree.query(l..r), lazy_tree.query(l..r));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_lazy_seg_tree_add_sum() {
+        let values: Vec<i64> = (0..37).map(|i| (i * 7919) % 101 - 50).collect();
+        check_matches_lazy::<LazySegTreeAddSumSpec<i64>>(&values, |x| (x % 21) as i64 - 10);
+    }
+
+    #[test]
+    fn matches_lazy_seg_tree_add_max() {
+        let values: Vec<i32> = (0..16).map(|i| (i * 31) % 17 - 8).collect();
+        check_matches_lazy::<LazySegTreeAddMaxSpec<i32>>(&values, |x| (x % 9) as i32 - 4);
+    }
+
+    /// Range assign with `(first, last)` queries; `op_on_data` is not
+    /// commutative, so combining nodes out of order gives wrong endpoints.
+    struct AssignFirstLast;
+
+    impl LazySegTreeSpec for AssignFirstLast {
+        type T = (Option<i64>, Option<i64>);
+        type U = i64;
+        const ID: Self::T = (None, None);
+
+        fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+            d1.0 = d1.0.or(d2.0);
+            d1.1 = d2.1.or(d1.1);
+        }
+
+        fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+            *u1 = *u2;
+        }
+
+        fn op_update_on_data(u: &Self::U, d: &mut Self::T, _size: usize) {
+            *d = (Some(*u), Some(*u));
+        }
+    }
+
+    #[test]
+    fn non_commutative_query_matches_brute_force() {
+        let mut values: Vec<i64> = (0..23).map(|i| i * 10).collect();
+        let mut tree = CopyLazySegTree::<AssignFirstLast>::from_vec(
+            values.iter().map(|&v| (Some(v), Some(v))).collect(),
+        );
+        let n = values.len();
+
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        for round in 0..200 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let a = ((state >> 33) % (n as u64 + 1)) as usize;
+            let b = ((state >> 17) % (n as u64 + 1)) as usize;
+            let (l, r) = (a.min(b), a.max(b));
+            if round % 3 == 0 {
+                tree.update(l..r, round);
+                values[l..r].fill(round);
+            }
+
+            for l in 0..=n {
+                for r in l..=n {
+                    let expected = (values[l..r].first().copied(), values[l..r].last().copied());
+                    assert_eq!(tree.query(l..r), expected, "[{}, {})", l, r);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn constructors_and_empty() {
+        let mut tree = CopyLazySegTree::<LazySegTreeAddSumSpec<i32>>::new(0);
+        assert!(tree.is_empty());
+        assert_eq!(tree.capacity(), 1);
+        assert_eq!(tree.query(..), 0);
+        tree.update(.., 5);
+        assert_eq!(tree.query(..), 0);
+
+        let mut tree = CopyLazySegTree::<LazySegTreeAddSumSpec<i32>>::new(5);
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.capacity(), 8);
+        tree.update(.., 2);
+        assert_eq!(tree.query(..), 10);
+    }
+
+    #[test]
+    fn is_sync() {
+        fn assert_sync<S: Sync>() {}
+        assert_sync::<CopyLazySegTree<LazySegTreeAddSumSpec<i64>>>();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_invalid_range() {
+        let mut tree = CopyLazySegTree::<LazySegTreeAddSumSpec<i32>>::new(3);
+        tree.query(1..5);
+    }
+}
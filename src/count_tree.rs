@@ -0,0 +1,197 @@
+//! Order-statistics multiset over a fixed value domain.
+//!
+//! [`CountTree`] stores one frequency per domain value in the leaves of a
+//! [`SegTree`], so inserting, removing and counting values in a range take
+//! O(log n), and the k-th smallest value is found by descending the tree.
+//!
+//! # Example
+//!
+//! ```rust
+//! use array_range_query::CountTree;
+//!
+//! let mut tree = CountTree::new(0..100u32);
+//! for x in [42, 7, 42, 99] {
+//!     tree.insert(x);
+//! }
+//! assert_eq!(tree.count_in(10..=42), 2);
+//! assert_eq!(tree.kth_smallest(0), Some(&7));
+//! assert_eq!(tree.kth_smallest(2), Some(&42));
+//! ```
+
+use crate::helpers::SegTreeSumSpec;
+use crate::{utils, SegTree};
+use alloc::vec::Vec;
+use core::ops::RangeBounds;
+
+/// A multiset of values from a fixed domain supporting counting and k-th
+/// smallest queries.
+///
+/// The domain is given up front, sorted and deduplicated; inserting a value
+/// outside it panics.
+pub struct CountTree<T: Ord> {
+    /// Sorted, deduplicated domain; value `domain[i]` counts in leaf `i`
+    domain: Vec<T>,
+    /// Frequency of each domain value
+    counts: SegTree<SegTreeSumSpec<usize>>,
+}
+
+impl<T: Ord> CountTree<T> {
+    // ===== CONSTRUCTORS =====
+
+    /// Creates an empty multiset over the given domain of values.
+    ///
+    /// # Time Complexity
+    /// O(m log m) for `m` domain values
+    pub fn new<I: IntoIterator<Item = T>>(domain: I) -> Self {
+        let mut domain: Vec<T> = domain.into_iter().collect();
+        domain.sort_unstable();
+        domain.dedup();
+        let counts = SegTree::new(domain.len());
+        Self { domain, counts }
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Returns the number of stored values, counting duplicates.
+    ///
+    /// # Time Complexity
+    /// O(log m)
+    pub fn len(&self) -> usize {
+        self.counts.query(..)
+    }
+
+    /// Returns `true` if no values are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds one occurrence of `value`.
+    ///
+    /// # Time Complexity
+    /// O(log m)
+    ///
+    /// # Panics
+    /// Panics if `value` is not part of the domain.
+    pub fn insert(&mut self, value: T) {
+        let index = self.index_of(&value);
+        let count = *self.counts.get(index);
+        self.counts.update(index, count + 1);
+    }
+
+    /// Removes one occurrence of `value`, returning `false` if none was
+    /// stored.
+    ///
+    /// # Time Complexity
+    /// O(log m)
+    ///
+    /// # Panics
+    /// Panics if `value` is not part of the domain.
+    pub fn remove(&mut self, value: &T) -> bool {
+        let index = self.index_of(value);
+        let count = *self.counts.get(index);
+        if count == 0 {
+            return false;
+        }
+        self.counts.update(index, count - 1);
+        true
+    }
+
+    /// Counts the stored values that fall inside `range`.
+    ///
+    /// # Time Complexity
+    /// O(log m)
+    ///
+    /// # Panics
+    /// Panics if both bounds are present and the start lies after the end.
+    pub fn count_in<R: RangeBounds<T>>(&self, range: R) -> usize {
+        let (left, right) = utils::compress_range(&self.domain, range);
+        self.counts.query(left..right)
+    }
+
+    /// Returns the `k`-th smallest stored value (0-based, duplicates counted
+    /// separately), or `None` if fewer than `k + 1` values are stored.
+    ///
+    /// # Time Complexity
+    /// O(log m)
+    pub fn kth_smallest(&self, k: usize) -> Option<&T> {
+        self.domain.get(self.counts.lower_bound(k.checked_add(1)?))
+    }
+
+    // ===== PRIVATE HELPER METHODS =====
+
+    fn index_of(&self, value: &T) -> usize {
+        self.domain
+            .binary_search(value)
+            .unwrap_or_else(|_| panic!("value is not part of the CountTree domain"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_insert_remove_count() {
+        let mut tree = CountTree::new([5, 1, 3, 9, 7]);
+        assert!(tree.is_empty());
+
+        tree.insert(3);
+        tree.insert(3);
+        tree.insert(9);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.count_in(..), 3);
+        assert_eq!(tree.count_in(2..4), 2);
+        assert_eq!(tree.count_in(4..), 1);
+
+        assert!(tree.remove(&3));
+        assert!(!tree.remove(&1));
+        assert_eq!(tree.count_in(..=3), 1);
+        assert_eq!(tree.kth_smallest(0), Some(&3));
+        assert_eq!(tree.kth_smallest(1), Some(&9));
+        assert_eq!(tree.kth_smallest(2), None);
+    }
+
+    #[test]
+    fn test_random_against_sorted_vec() {
+        let mut rng = rand::rng();
+        let mut tree = CountTree::new(-50..50i32);
+        let mut model: Vec<i32> = Vec::new();
+
+        for _ in 0..2000 {
+            match rng.random_range(0..4) {
+                0 | 1 => {
+                    let x = rng.random_range(-50..50);
+                    tree.insert(x);
+                    let pos = model.partition_point(|&v| v < x);
+                    model.insert(pos, x);
+                }
+                2 => {
+                    let x = rng.random_range(-50..50);
+                    let expected = model.iter().position(|&v| v == x);
+                    assert_eq!(tree.remove(&x), expected.is_some());
+                    if let Some(pos) = expected {
+                        model.remove(pos);
+                    }
+                }
+                _ => {
+                    let a = rng.random_range(-60..60);
+                    let b = rng.random_range(a..=60);
+                    let expected = model.iter().filter(|&&v| a <= v && v < b).count();
+                    assert_eq!(tree.count_in(a..b), expected);
+
+                    let k = rng.random_range(0..=model.len());
+                    assert_eq!(tree.kth_smallest(k), model.get(k));
+                }
+            }
+            assert_eq!(tree.len(), model.len());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not part of the CountTree domain")]
+    fn test_panic_insert_outside_domain() {
+        let mut tree = CountTree::new(0..10);
+        tree.insert(10);
+    }
+}
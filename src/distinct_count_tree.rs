@@ -0,0 +1,113 @@
+//! Offline distinct-value counting over ranges.
+//!
+//! [`DistinctCountTree`] answers "how many distinct values lie in `[l, r)`"
+//! for a batch of ranges with the last-occurrence trick: sweeping the array
+//! left to right, only the latest occurrence of each value holds a `1` in a
+//! sum [`SegTree`], so the distinct count of any range ending at the sweep
+//! position is a plain range sum.
+//!
+//! # Example
+//!
+//! ```rust
+//! use array_range_query::DistinctCountTree;
+//!
+//! let array = [1, 2, 1, 3, 2, 2];
+//! let answers = DistinctCountTree::answer_offline(&array, &[0..3, 1..6, 4..6, 2..2]);
+//! assert_eq!(answers, vec![2, 3, 1, 0]);
+//! ```
+
+use crate::helpers::SegTreeSumSpec;
+use crate::{utils, SegTree};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Offline solver for distinct-value counts over many ranges.
+pub struct DistinctCountTree;
+
+impl DistinctCountTree {
+    /// Returns the number of distinct values of `array` in each query range,
+    /// in query order.
+    ///
+    /// # Time Complexity
+    /// O((n + q) log n) for `n` elements and `q` queries
+    ///
+    /// # Panics
+    /// Panics if any range is invalid or out of bounds.
+    pub fn answer_offline<T: Ord>(array: &[T], queries: &[Range<usize>]) -> Vec<usize> {
+        for query in queries {
+            utils::validate_range(query.start, query.end, array.len());
+        }
+
+        // Process queries by right end so each is answered once the sweep
+        // has covered it.
+        let mut order: Vec<usize> = (0..queries.len()).collect();
+        order.sort_unstable_by_key(|&i| queries[i].end);
+
+        let mut marks = SegTree::<SegTreeSumSpec<usize>>::new(array.len());
+        let mut last: BTreeMap<&T, usize> = BTreeMap::new();
+        let mut answers = alloc::vec![0; queries.len()];
+        let mut swept = 0;
+
+        for i in order {
+            let Range { start, end } = queries[i];
+            while swept < end {
+                if let Some(prev) = last.insert(&array[swept], swept) {
+                    marks.update(prev, 0);
+                }
+                marks.update(swept, 1);
+                swept += 1;
+            }
+            answers[i] = marks.query(start..end);
+        }
+        answers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::collections::BTreeSet;
+
+    fn brute_force(array: &[u8], range: Range<usize>) -> usize {
+        array[range].iter().collect::<BTreeSet<_>>().len()
+    }
+
+    #[test]
+    fn test_all_ranges_against_brute_force() {
+        let array = [3u8, 1, 3, 3, 2, 1, 4, 2];
+        let queries: Vec<Range<usize>> = (0..=array.len())
+            .flat_map(|l| (l..=array.len()).map(move |r| l..r))
+            .collect();
+        let answers = DistinctCountTree::answer_offline(&array, &queries);
+        for (query, answer) in queries.into_iter().zip(answers) {
+            assert_eq!(answer, brute_force(&array, query));
+        }
+    }
+
+    #[test]
+    fn test_random_against_brute_force() {
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let size = rng.random_range(0..30);
+            let array: Vec<u8> = (0..size).map(|_| rng.random_range(0..6)).collect();
+            let queries: Vec<Range<usize>> = (0..40)
+                .map(|_| {
+                    let l = rng.random_range(0..=size);
+                    l..rng.random_range(l..=size)
+                })
+                .collect();
+            let answers = DistinctCountTree::answer_offline(&array, &queries);
+            for (query, &answer) in queries.iter().zip(&answers) {
+                assert_eq!(answer, brute_force(&array, query.clone()));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_query_out_of_bounds() {
+        DistinctCountTree::answer_offline(&[1, 2, 3], &[0..2, 1..4]);
+    }
+}
@@ -0,0 +1,322 @@
+//! Lazy segment tree driven by closures instead of a spec type.
+//!
+//! [`LazySegTree`](crate::LazySegTree) takes its operations from a
+//! [`LazySegTreeSpec`](crate::LazySegTreeSpec) implementation, which is heavy
+//! for a one-off update. [`DynLazySegTree`] takes the same three operations
+//! as boxed closures and the identity as a runtime value, so it can be set up
+//! inline while prototyping. Every operation goes through a dynamic call,
+//! so it is somewhat slower than the spec-based tree.
+
+use crate::{utils, SegTreeNode};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::ops::RangeBounds;
+
+type OpOnData<T> = Box<dyn Fn(&mut T, &T)>;
+type OpOnUpdate<U> = Box<dyn Fn(&mut U, &U)>;
+type OpUpdateOnData<T, U> = Box<dyn Fn(&U, &mut T, usize)>;
+
+/// Lazy segment tree whose operations are closures supplied at construction.
+///
+/// The closures play the roles of
+/// [`LazySegTreeSpec::op_on_data`](crate::LazySegTreeSpec::op_on_data),
+/// [`op_on_update`](crate::LazySegTreeSpec::op_on_update) and
+/// [`op_update_on_data`](crate::LazySegTreeSpec::op_update_on_data), and must
+/// satisfy the same laws.
+///
+/// # Example
+///
+/// ```rust
+/// use array_range_query::DynLazySegTree;
+///
+/// // Range assign, range max
+/// let mut tree = DynLazySegTree::from_vec(
+///     vec![3, 1, 4, 1, 5],
+///     i32::MIN,
+///     |a: &mut i32, b: &i32| *a = (*a).max(*b),
+///     |u1: &mut i32, u2: &i32| *u1 = *u2,
+///     |u: &i32, d: &mut i32, _size: usize| *d = *u,
+/// );
+/// assert_eq!(tree.query(..3), 4);
+/// tree.update(1..3, 2);
+/// assert_eq!(tree.query(..3), 3);
+/// ```
+pub struct DynLazySegTree<T, U> {
+    size: usize,
+    max_size: usize,
+    max_depth: u32,
+    data: Box<[T]>,
+    tags: Box<[Option<U>]>,
+    id: T,
+    op_on_data: OpOnData<T>,
+    op_on_update: OpOnUpdate<U>,
+    op_update_on_data: OpUpdateOnData<T, U>,
+}
+
+impl<T: Clone, U: Clone> DynLazySegTree<T, U> {
+    // ===== CONSTRUCTORS =====
+
+    /// Creates a new tree from a vector of values, the identity `id` and the
+    /// three operations.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_vec(
+        values: Vec<T>,
+        id: T,
+        op_on_data: impl Fn(&mut T, &T) + 'static,
+        op_on_update: impl Fn(&mut U, &U) + 'static,
+        op_update_on_data: impl Fn(&U, &mut T, usize) + 'static,
+    ) -> Self {
+        let size = values.len();
+        let max_size = size.next_power_of_two();
+        let mut data = values;
+        data.resize(max_size, id.clone());
+        // Internal nodes go in front of the leaves
+        data.splice(0..0, vec![id.clone(); max_size]);
+
+        for i in (1..max_size).rev() {
+            let mut v = data[i * 2].clone();
+            op_on_data(&mut v, &data[i * 2 + 1]);
+            data[i] = v;
+        }
+
+        Self {
+            size,
+            max_size,
+            max_depth: max_size.trailing_zeros(),
+            data: data.into_boxed_slice(),
+            tags: vec![None; max_size * 2].into_boxed_slice(),
+            id,
+            op_on_data: Box::new(op_on_data),
+            op_on_update: Box::new(op_on_update),
+            op_update_on_data: Box::new(op_update_on_data),
+        }
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Returns the number of elements in the tree.
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the tree holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Queries the aggregated value over the given range, pushing pending
+    /// tags on the boundary paths.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&mut self, range: R) -> T {
+        let (left_inp, right_inp) = utils::parse_range(range, self.size);
+        utils::validate_range(left_inp, right_inp, self.size);
+        if left_inp == right_inp {
+            return self.id.clone();
+        }
+
+        let mut l = self.max_size + left_inp;
+        let mut r = self.max_size + right_inp;
+        self.push_boundaries(l, r);
+
+        let mut res_left = self.id.clone();
+        let mut res_right = self.id.clone();
+
+        while l < r {
+            if l & 1 != 0 {
+                (self.op_on_data)(&mut res_left, &self.eval(SegTreeNode(l)));
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                let mut v = self.eval(SegTreeNode(r));
+                (self.op_on_data)(&mut v, &res_right);
+                res_right = v;
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+
+        (self.op_on_data)(&mut res_left, &res_right);
+        res_left
+    }
+
+    /// Applies an update to all elements in the given range.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn update<R: RangeBounds<usize>>(&mut self, range: R, value: U) {
+        let (left_inp, right_inp) = utils::parse_range(range, self.size);
+        utils::validate_range(left_inp, right_inp, self.size);
+        if left_inp == right_inp {
+            return;
+        }
+
+        let mut l = self.max_size + left_inp;
+        let mut r = self.max_size + right_inp;
+        self.push_boundaries(l, r);
+
+        let l0 = l;
+        let r0 = r;
+
+        while l < r {
+            if l & 1 != 0 {
+                self.combine_tag(l, &value);
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                self.combine_tag(r, &value);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+
+        for i in 1..=self.max_depth {
+            if ((l0 >> i) << i) != l0 {
+                self.pull_node(SegTreeNode(l0 >> i));
+            }
+            if ((r0 >> i) << i) != r0 {
+                self.pull_node(SegTreeNode((r0 - 1) >> i));
+            }
+        }
+    }
+
+    // ===== PRIVATE HELPER METHODS =====
+
+    /// Pushes the tags of every proper ancestor of the leaf boundaries `l`
+    /// and `r` that does not start exactly at that boundary, root first.
+    fn push_boundaries(&mut self, l: usize, r: usize) {
+        for i in (1..=self.max_depth).rev() {
+            if ((l >> i) << i) != l {
+                self.push_node(SegTreeNode(l >> i));
+            }
+            if ((r >> i) << i) != r {
+                self.push_node(SegTreeNode((r - 1) >> i));
+            }
+        }
+    }
+
+    fn pull_node(&mut self, node: SegTreeNode) {
+        if node.is_leaf(self.max_depth) {
+            return;
+        }
+        let mut res = self.eval(node.left_child());
+        (self.op_on_data)(&mut res, &self.eval(node.right_child()));
+        self.data[node.0] = res;
+    }
+
+    fn eval(&self, node: SegTreeNode) -> T {
+        let mut d = self.data[node.0].clone();
+        if let Some(tag) = &self.tags[node.0] {
+            (self.op_update_on_data)(tag, &mut d, node.size(self.max_depth));
+        }
+        d
+    }
+
+    /// Pushes the tag of the current node to its children after consuming it.
+    fn push_node(&mut self, node: SegTreeNode) {
+        if let Some(tag) = self.tags[node.0].take() {
+            let size = node.size(self.max_depth);
+            (self.op_update_on_data)(&tag, &mut self.data[node.0], size);
+            if !node.is_leaf(self.max_depth) {
+                self.combine_tag(node.left_child().0, &tag);
+                self.combine_tag(node.right_child().0, &tag);
+            }
+        }
+    }
+
+    fn combine_tag(&mut self, index: usize, new_tag: &U) {
+        match &mut self.tags[index] {
+            Some(existing) => (self.op_on_update)(existing, new_tag),
+            slot => *slot = Some(new_tag.clone()),
+        }
+    }
+}
+
+// ===== TESTS =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LazySegTreeAddSum;
+
+    fn add_sum(values: Vec<i64>) -> DynLazySegTree<i64, i64> {
+        DynLazySegTree::from_vec(
+            values,
+            0,
+            |a, b| *a += *b,
+            |u1, u2| *u1 += *u2,
+            |u, d, size| *d += u * size as i64,
+        )
+    }
+
+    #[test]
+    fn matches_lazy_seg_tree_add_sum() {
+        let values: Vec<i64> = (0..37).map(|i| (i * 7919) % 101 - 50).collect();
+        let n = values.len() as u64;
+        let mut dyn_tree = add_sum(values.clone());
+        let mut lazy_tree = LazySegTreeAddSum::<i64>::from_vec(values);
+
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        for _ in 0..500 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let a = ((state >> 33) % (n + 1)) as usize;
+            let b = ((state >> 17) % (n + 1)) as usize;
+            let (l, r) = (a.min(b), a.max(b));
+            if state & 1 == 0 {
+                let u = ((state >> 40) % 21) as i64 - 10;
+                dyn_tree.update(l..r, u);
+                lazy_tree.update(l..r, u);
+            } else {
+                assert_eq!(dyn_tree.query(l..r), lazy_tree.query(l..r));
+            }
+        }
+
+        for l in 0..=n as usize {
+            for r in l..=n as usize {
+                assert_eq!(dyn_tree.query(l..r), lazy_tree.query(l..r));
+            }
+        }
+    }
+
+    #[test]
+    fn closures_can_capture_state() {
+        let modulus = 7;
+        let mut tree = DynLazySegTree::from_vec(
+            vec![1u64, 2, 3, 4],
+            0,
+            move |a, b| *a = (*a + *b) % modulus,
+            move |u1, u2| *u1 = (*u1 + *u2) % modulus,
+            move |u, d, size| *d = (*d + u * size as u64) % modulus,
+        );
+        assert_eq!(tree.query(..), 3);
+        tree.update(1..3, 6);
+        assert_eq!(tree.query(..), (10 + 12) % 7);
+    }
+
+    #[test]
+    fn empty_tree() {
+        let mut tree = add_sum(Vec::new());
+        assert!(tree.is_empty());
+        assert_eq!(tree.query(..), 0);
+        tree.update(.., 5);
+        assert_eq!(tree.query(..), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_invalid_range() {
+        add_sum(vec![1, 2, 3]).query(1..5);
+    }
+}
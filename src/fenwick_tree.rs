@@ -0,0 +1,217 @@
+//! Fenwick tree (binary indexed tree) for point-add / prefix-sum workloads.
+//!
+//! [`FenwickTree`] stores `n + 1` values instead of the `2 * n.next_power_of_two()`
+//! of a [`SegTree`](crate::SegTree), and its loops touch fewer nodes. It only
+//! supports invertible sums: adding a delta at a point and summing a prefix
+//! or range.
+//!
+//! # Example
+//!
+//! ```rust
+//! use array_range_query::FenwickTree;
+//!
+//! let mut tree = FenwickTree::<i64>::from_slice(&[3, 1, 4, 1, 5]);
+//! tree.add(2, 10);
+//! assert_eq!(tree.prefix_sum(3), 18);
+//! assert_eq!(tree.range_sum(1..4), 16);
+//! assert_eq!(tree.lower_bound(5), 2);
+//! ```
+
+use crate::utils;
+use alloc::{boxed::Box, vec};
+use core::ops::{Add, RangeBounds, Sub};
+use num_traits::ConstZero;
+
+/// Binary indexed tree over `T` supporting point adds and range sums.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FenwickTree<T> {
+    /// 1-based partial sums; `data[i]` covers `(i - lowbit(i), i]`
+    data: Box<[T]>,
+}
+
+impl<T> FenwickTree<T>
+where
+    T: Copy + ConstZero + Add<Output = T> + Sub<Output = T>,
+{
+    // ===== CONSTRUCTORS =====
+
+    /// Creates a tree of `size` zeros.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn new(size: usize) -> Self {
+        Self {
+            data: vec![T::ZERO; size + 1].into_boxed_slice(),
+        }
+    }
+
+    /// Creates a tree from a slice of values.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_slice(values: &[T]) -> Self {
+        let mut data = vec![T::ZERO; values.len() + 1];
+        data[1..].copy_from_slice(values);
+        // Push each partial sum into the parent that covers it
+        for i in 1..data.len() {
+            let parent = i + lowbit(i);
+            if parent < data.len() {
+                data[parent] = data[parent] + data[i];
+            }
+        }
+        Self {
+            data: data.into_boxed_slice(),
+        }
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.data.len() - 1
+    }
+
+    /// Returns `true` if the tree holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds `delta` to the element at `index`.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn add(&mut self, index: usize, delta: T) {
+        assert!(index < self.len(), "add index out of bounds");
+        let mut i = index + 1;
+        while i < self.data.len() {
+            self.data[i] = self.data[i] + delta;
+            i += lowbit(i);
+        }
+    }
+
+    /// Returns the sum of the first `end` elements, i.e. of `..end`.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `end > len`.
+    pub fn prefix_sum(&self, end: usize) -> T {
+        utils::validate_range(0, end, self.len());
+        let mut sum = T::ZERO;
+        let mut i = end;
+        while i > 0 {
+            sum = sum + self.data[i];
+            i -= lowbit(i);
+        }
+        sum
+    }
+
+    /// Returns the sum over the given range.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn range_sum<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (left, right) = utils::parse_range(range, self.len());
+        utils::validate_range(left, right, self.len());
+        self.prefix_sum(right) - self.prefix_sum(left)
+    }
+
+    /// Returns the smallest index `i` such that `prefix_sum(i + 1) >= target`,
+    /// or `len` if no prefix reaches `target`.
+    ///
+    /// Like [`SegTree::lower_bound`](crate::SegTree::lower_bound), this
+    /// assumes the elements are non-negative so prefix sums are monotone.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    pub fn lower_bound(&self, target: T) -> usize
+    where
+        T: PartialOrd,
+    {
+        let n = self.len();
+        let mut pos = 0;
+        let mut acc = T::ZERO;
+        let mut step = if n == 0 { 0 } else { 1 << n.ilog2() };
+        while step > 0 {
+            if pos + step <= n && acc + self.data[pos + step] < target {
+                pos += step;
+                acc = acc + self.data[pos];
+            }
+            step /= 2;
+        }
+        pos
+    }
+}
+
+/// Lowest set bit of `i`.
+#[inline]
+fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SegTreeSum;
+    use rand::Rng;
+
+    #[test]
+    fn test_from_slice_matches_adds() {
+        let values = [5i64, -3, 8, 0, 2, 7, -1];
+        let built = FenwickTree::from_slice(&values);
+        let mut added = FenwickTree::new(values.len());
+        for (i, &v) in values.iter().enumerate() {
+            added.add(i, v);
+        }
+        assert_eq!(built, added);
+        for end in 0..=values.len() {
+            assert_eq!(built.prefix_sum(end), values[..end].iter().sum::<i64>());
+        }
+    }
+
+    #[test]
+    fn test_random_against_seg_tree_sum() {
+        let mut rng = rand::rng();
+        let size = 37;
+        let mut fenwick = FenwickTree::<i64>::new(size);
+        let mut seg_tree = SegTreeSum::<i64>::new(size);
+
+        for _ in 0..500 {
+            let i = rng.random_range(0..size);
+            let delta = rng.random_range(-50..50);
+            fenwick.add(i, delta);
+            seg_tree.update(i, seg_tree.get(i) + delta);
+
+            let l = rng.random_range(0..=size);
+            let r = rng.random_range(l..=size);
+            assert_eq!(fenwick.range_sum(l..r), seg_tree.query(l..r));
+        }
+    }
+
+    #[test]
+    fn test_lower_bound_matches_seg_tree() {
+        let mut rng = rand::rng();
+        for size in [0, 1, 5, 16, 23] {
+            let values: Vec<u32> = (0..size).map(|_| rng.random_range(0..5)).collect();
+            let fenwick = FenwickTree::from_slice(&values);
+            let seg_tree = SegTreeSum::<u32>::from_slice(&values);
+            let total: u32 = values.iter().sum();
+            for target in 0..=total + 1 {
+                assert_eq!(fenwick.lower_bound(target), seg_tree.lower_bound(target));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "add index out of bounds")]
+    fn test_panic_add_out_of_bounds() {
+        FenwickTree::<i32>::new(3).add(3, 1);
+    }
+}
@@ -0,0 +1,94 @@
+//! Read-only snapshot of a [`LazySegTree`](crate::LazySegTree).
+//!
+//! [`FrozenLazySegTree`] is produced by
+//! [`LazySegTree::freeze`](crate::LazySegTree::freeze), which pushes every
+//! pending tag down so each node holds its final aggregate. Queries then need
+//! no interior mutability, so the frozen tree is `Sync` whenever its values
+//! are and can be shared across threads, e.g. behind an `Arc`.
+
+use crate::{utils, LazySegTreeSpec};
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
+
+/// Immutable lazy segment tree with all tags applied.
+///
+/// # Example
+///
+/// ```rust
+/// use array_range_query::LazySegTreeAddSum;
+/// use std::sync::Arc;
+///
+/// let mut tree = LazySegTreeAddSum::<i64>::from_vec(vec![1, 2, 3, 4]);
+/// tree.update(1..3, 10);
+/// let frozen = Arc::new(tree.freeze());
+///
+/// let shared = Arc::clone(&frozen);
+/// let sum = std::thread::spawn(move || shared.query(..)).join().unwrap();
+/// assert_eq!(sum, 30);
+/// assert_eq!(frozen.query(1..2), 12);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrozenLazySegTree<Spec: LazySegTreeSpec> {
+    size: usize,
+    max_size: usize,
+    data: Box<[Spec::T]>,
+    _spec: PhantomData<Spec>,
+}
+
+impl<Spec: LazySegTreeSpec> FrozenLazySegTree<Spec> {
+    /// Wraps flushed node values; `data` must hold no pending tags.
+    pub(crate) fn from_parts(size: usize, max_size: usize, data: Box<[Spec::T]>) -> Self {
+        Self {
+            size,
+            max_size,
+            data,
+            _spec: PhantomData,
+        }
+    }
+
+    /// Returns the number of logical elements.
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the tree holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Queries the aggregated value over the given range.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> Spec::T {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+
+        let mut left = left + self.max_size;
+        let mut right = right + self.max_size;
+        let mut result_left = Spec::ID;
+        let mut result_right = Spec::ID;
+
+        while left < right {
+            if left & 1 == 1 {
+                Spec::op_on_data(&mut result_left, &self.data[left]);
+                left += 1;
+            }
+            if right & 1 == 1 {
+                right -= 1;
+                let mut v = self.data[right].clone();
+                Spec::op_on_data(&mut v, &result_right);
+                result_right = v;
+            }
+            left /= 2;
+            right /= 2;
+        }
+
+        Spec::op_on_data(&mut result_left, &result_right);
+        result_left
+    }
+}
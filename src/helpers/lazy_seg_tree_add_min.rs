@@ -3,9 +3,9 @@
 //! Provides `LazySegTreeAddMin<T>` for efficient range addition with minimum aggregation.
 
 use crate::{LazySegTree, LazySegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::Add;
 use min_max_traits::Max as ConstUpperBound;
-use std::marker::PhantomData;
-use std::ops::Add;
 
 /// Specification for range add updates with minimum queries.
 pub struct LazySegTreeAddMinSpec<T>(PhantomData<T>);
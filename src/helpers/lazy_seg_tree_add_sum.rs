@@ -3,9 +3,9 @@
 //! Provides `LazySegTreeAddSum<T>` for efficient range addition with sum aggregation.
 
 use crate::{LazySegTree, LazySegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::Add;
 use num_traits::ConstZero;
-use std::marker::PhantomData;
-use std::ops::Add;
 
 /// Specification for range add updates with sum queries.
 pub struct LazySegTreeAddSumSpec<T>(PhantomData<T>);
@@ -33,6 +33,10 @@ where
             *d = d.clone() + u.clone();
         }
     }
+
+    fn is_identity_update(u: &Self::U) -> bool {
+        u.is_zero()
+    }
 }
 
 /// Lazy segment tree specialized for range add updates and sum queries.
@@ -227,4 +231,22 @@ mod tests {
             assert!(range_sum >= 0); // Should be valid
         }
     }
+
+    #[test]
+    fn test_add_sum_identity_update_skips_tags() {
+        let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+        tree.update(1..4, 2);
+        let pending = tree.pending_tag_count();
+        let total = tree.query(..);
+
+        // Adding zero must leave both the values and the tag layout untouched
+        tree.update(..3, 0);
+        tree.update(2.., 0);
+        assert_eq!(tree.pending_tag_count(), pending);
+        assert_eq!(tree.query(..), total);
+
+        let mut fresh = LazySegTreeAddSum::<i32>::new(8);
+        fresh.update(.., 0);
+        assert_eq!(fresh.pending_tag_count(), 0);
+    }
 }
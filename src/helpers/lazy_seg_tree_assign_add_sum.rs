@@ -0,0 +1,135 @@
+//! Lazy segment tree for mixed range assignment and range add updates with
+//! sum queries.
+//!
+//! Provides `LazySegTreeAssignAddSum<T>`, whose updates are [`AssignAdd`]
+//! values.
+
+use crate::{LazySegTree, LazySegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::{Add, Mul};
+use num_traits::{ConstZero, NumCast};
+
+/// Update applied by [`LazySegTreeAssignAddSum`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssignAdd<T> {
+    /// Sets every element in the range to the value.
+    Assign(T),
+    /// Adds the value to every element in the range.
+    Add(T),
+}
+
+/// Specification for range assign / range add updates with sum queries.
+///
+/// Pending updates compose so that a node holds at most one tag: an
+/// `Assign` overrides whatever came before, and an `Add` after an
+/// `Assign(x)` folds into `Assign(x + add)`.
+pub struct LazySegTreeAssignAddSumSpec<T>(PhantomData<T>);
+
+impl<T> LazySegTreeSpec for LazySegTreeAssignAddSumSpec<T>
+where
+    T: Clone + ConstZero + Add<Output = T> + Mul<Output = T> + NumCast,
+{
+    type T = T;
+    type U = AssignAdd<T>;
+
+    const ID: Self::T = <T as ConstZero>::ZERO;
+
+    fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+        *d1 = d1.clone() + d2.clone();
+    }
+
+    fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+        *u1 = match (&*u1, u2) {
+            (_, AssignAdd::Assign(x)) => AssignAdd::Assign(x.clone()),
+            (AssignAdd::Assign(y), AssignAdd::Add(x)) => AssignAdd::Assign(y.clone() + x.clone()),
+            (AssignAdd::Add(y), AssignAdd::Add(x)) => AssignAdd::Add(y.clone() + x.clone()),
+        };
+    }
+
+    fn op_update_on_data(u: &Self::U, d: &mut Self::T, size: usize) {
+        let size = T::from(size).unwrap_or_else(|| panic!("Failed to convert usize to T"));
+        *d = match u {
+            AssignAdd::Assign(x) => x.clone() * size,
+            AssignAdd::Add(x) => d.clone() + x.clone() * size,
+        };
+    }
+
+    fn is_identity_update(u: &Self::U) -> bool {
+        matches!(u, AssignAdd::Add(x) if x.is_zero())
+    }
+}
+
+/// Convenience alias: a `LazySegTree` specialized for range assign and range
+/// add updates with sum queries.
+///
+/// # Examples
+///
+/// ```
+/// use array_range_query::helpers::AssignAdd;
+/// use array_range_query::LazySegTreeAssignAddSum;
+///
+/// let mut tree = LazySegTreeAssignAddSum::<i64>::from_vec(vec![1, 2, 3, 4, 5]);
+///
+/// tree.update(1..4, AssignAdd::Assign(10));
+/// assert_eq!(tree.query(..), 1 + 10 + 10 + 10 + 5);
+///
+/// tree.update(2.., AssignAdd::Add(1));
+/// assert_eq!(tree.query(..), 1 + 10 + 11 + 11 + 6);
+/// ```
+pub type LazySegTreeAssignAddSum<T> = LazySegTree<LazySegTreeAssignAddSumSpec<T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_update_composition() {
+        let mut tree = LazySegTreeAssignAddSum::<i64>::new(8);
+        tree.update(.., AssignAdd::Add(2));
+        tree.update(.., AssignAdd::Assign(5));
+        tree.update(.., AssignAdd::Add(3));
+        assert_eq!(tree.query(..), 64);
+        assert_eq!(tree.query(3..4), 8);
+
+        tree.update(2..6, AssignAdd::Add(-8));
+        tree.update(4..8, AssignAdd::Assign(1));
+        assert_eq!(tree.query(..), 20); // 8, 8, 0, 0, 1, 1, 1, 1
+    }
+
+    #[test]
+    fn test_zero_add_is_identity() {
+        let mut tree = LazySegTreeAssignAddSum::<i32>::from_vec(vec![1, 2, 3]);
+        tree.update(.., AssignAdd::Add(0));
+        assert_eq!(tree.pending_tag_count(), 0);
+        assert_eq!(tree.query(..), 6);
+    }
+
+    #[test]
+    fn test_randomized_against_brute_force() {
+        let mut rng = rand::rng();
+        let size = 200;
+
+        for _ in 0..5 {
+            let mut values: Vec<i64> = (0..size).map(|_| rng.random_range(-1000..=1000)).collect();
+            let mut tree = LazySegTreeAssignAddSum::<i64>::from_slice(&values);
+
+            for _ in 0..300 {
+                let l = rng.random_range(0..size);
+                let r = rng.random_range(l..=size);
+                let x = rng.random_range(-100..=100);
+                match rng.random_range(0..3) {
+                    0 => {
+                        values[l..r].iter_mut().for_each(|v| *v = x);
+                        tree.update(l..r, AssignAdd::Assign(x));
+                    }
+                    1 => {
+                        values[l..r].iter_mut().for_each(|v| *v += x);
+                        tree.update(l..r, AssignAdd::Add(x));
+                    }
+                    _ => assert_eq!(tree.query(l..r), values[l..r].iter().sum::<i64>()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Lazy segment tree for range add updates and overflow-checked sum queries.
+//!
+//! Provides `LazySegTreeCheckedAddSum<T>`, which panics instead of wrapping
+//! when a sum or pending update leaves the range of `T`.
+
+use crate::{LazySegTree, LazySegTreeSpec};
+use core::marker::PhantomData;
+use num_traits::{CheckedAdd, CheckedMul, ConstZero, NumCast};
+
+/// Specification for range add updates with overflow-checked sum queries.
+///
+/// Every addition and the `update * size` scaling use checked arithmetic and
+/// panic with "segment tree sum overflow" when the result does not fit in `T`.
+pub struct LazySegTreeCheckedAddSumSpec<T>(PhantomData<T>);
+
+impl<T> LazySegTreeCheckedAddSumSpec<T>
+where
+    T: CheckedAdd,
+{
+    fn add(a: &T, b: &T) -> T {
+        a.checked_add(b).expect("segment tree sum overflow")
+    }
+}
+
+impl<T> LazySegTreeSpec for LazySegTreeCheckedAddSumSpec<T>
+where
+    T: Clone + CheckedAdd + CheckedMul + ConstZero + NumCast,
+{
+    type T = T;
+    type U = T;
+
+    const ID: Self::T = <T as ConstZero>::ZERO;
+
+    fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+        *d1 = Self::add(d1, d2);
+    }
+
+    fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+        *u1 = Self::add(u1, u2);
+    }
+
+    fn op_update_on_data(u: &Self::U, d: &mut Self::T, size: usize) {
+        if u.is_zero() {
+            return;
+        }
+        let total = <T as NumCast>::from(size)
+            .and_then(|n| u.checked_mul(&n))
+            .expect("segment tree sum overflow");
+        *d = Self::add(d, &total);
+    }
+
+    fn is_identity_update(u: &Self::U) -> bool {
+        u.is_zero()
+    }
+}
+
+/// Lazy segment tree specialized for range add updates and sum queries that
+/// panic on overflow instead of wrapping.
+///
+/// # Examples
+///
+/// ```rust
+/// use array_range_query::LazySegTreeCheckedAddSum;
+///
+/// let mut tree = LazySegTreeCheckedAddSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+/// tree.update(1..4, 10);
+/// assert_eq!(tree.query(..), 45);
+/// ```
+///
+/// ```rust,should_panic
+/// use array_range_query::LazySegTreeCheckedAddSum;
+///
+/// let mut tree = LazySegTreeCheckedAddSum::<i32>::from_vec(vec![0; 4]);
+/// tree.update(.., i32::MAX / 2);
+/// tree.query(..); // panics: segment tree sum overflow
+/// ```
+pub type LazySegTreeCheckedAddSum<T> = LazySegTree<LazySegTreeCheckedAddSumSpec<T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked_add_sum_matches_unchecked() {
+        let mut tree = LazySegTreeCheckedAddSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+
+        assert_eq!(tree.query(..), 15);
+        tree.update(1..4, 10);
+        assert_eq!(tree.query(..), 45);
+        tree.update(..2, -3);
+        assert_eq!(tree.query(..2), 7);
+        assert_eq!(tree.query(..), 39);
+    }
+
+    #[test]
+    fn test_checked_add_sum_near_limit() {
+        let mut tree = LazySegTreeCheckedAddSum::<i32>::from_vec(vec![i32::MAX - 10, 0]);
+        tree.update(..1, 10);
+        assert_eq!(tree.query(..1), i32::MAX);
+
+        // Updates that cancel out never scale a zero tag
+        let mut small = LazySegTreeCheckedAddSum::<i8>::new(100);
+        small.update(.., 1);
+        small.update(.., -1);
+        assert_eq!(small.query(..), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "segment tree sum overflow")]
+    fn test_checked_add_sum_overflow_panics() {
+        let mut tree = LazySegTreeCheckedAddSum::<i32>::from_vec(vec![i32::MAX, 0, 0, 0]);
+        tree.update(1..2, 1);
+        tree.query(..);
+    }
+
+    #[test]
+    #[should_panic(expected = "segment tree sum overflow")]
+    fn test_checked_add_sum_scaled_update_overflow_panics() {
+        let mut tree = LazySegTreeCheckedAddSum::<i32>::new(1 << 20);
+        tree.update(.., 1 << 12);
+        tree.query(..);
+    }
+}
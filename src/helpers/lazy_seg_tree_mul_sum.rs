@@ -0,0 +1,135 @@
+//! Lazy segment tree for range multiply updates and sum queries.
+//!
+//! Provides `LazySegTreeMulSum<T>` for efficient range scaling with sum aggregation.
+
+use crate::{LazySegTree, LazySegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::{Add, Mul};
+use num_traits::{ConstOne, ConstZero};
+
+/// Specification for range multiply updates with sum queries.
+///
+/// Multiplication distributes over the sum, so applying a factor to a node
+/// scales its stored sum directly, independent of the node size. Pending
+/// factors compose by multiplication and `T::ONE` is the identity update.
+///
+/// Mixing range adds and range multiplies on the same tree needs an affine
+/// update `(mul, add)` instead; this spec only supports scaling.
+pub struct LazySegTreeMulSumSpec<T>(PhantomData<T>);
+
+impl<T> LazySegTreeSpec for LazySegTreeMulSumSpec<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T> + ConstZero + ConstOne + PartialEq,
+{
+    type T = T;
+    type U = T;
+
+    const ID: Self::T = <T as ConstZero>::ZERO;
+
+    fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+        *d1 = d1.clone() + d2.clone();
+    }
+
+    fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+        *u1 = u1.clone() * u2.clone();
+    }
+
+    fn op_update_on_data(u: &Self::U, d: &mut Self::T, _size: usize) {
+        *d = d.clone() * u.clone();
+    }
+
+    fn is_identity_update(u: &Self::U) -> bool {
+        u.is_one()
+    }
+}
+
+/// Lazy segment tree specialized for range multiply updates and sum queries.
+///
+/// # Examples
+///
+/// ```rust
+/// use array_range_query::LazySegTreeMulSum;
+///
+/// let mut tree = LazySegTreeMulSum::<i64>::from_vec(vec![1, 2, 3, 4, 5]);
+///
+/// assert_eq!(tree.query(..), 15);
+///
+/// // Multiply range [1, 4) by 3
+/// tree.update(1..4, 3);
+/// assert_eq!(tree.query(..), 33); // 1 + 6 + 9 + 12 + 5
+/// ```
+pub type LazySegTreeMulSum<T> = LazySegTree<LazySegTreeMulSumSpec<T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_mul_sum_basic_operations() {
+        let mut tree = LazySegTreeMulSum::<i64>::from_vec(vec![1, 2, 3, 4, 5]);
+
+        assert_eq!(tree.query(..), 15);
+        tree.update(..3, 2); // [2, 4, 6, 4, 5]
+        assert_eq!(tree.query(..), 21);
+        tree.update(2..5, -1); // [2, 4, -6, -4, -5]
+        assert_eq!(tree.query(..), -9);
+        assert_eq!(tree.query(1..3), -2);
+        assert_eq!(tree.query(3..3), 0);
+    }
+
+    #[test]
+    fn test_mul_sum_identity_update_skips_tags() {
+        let mut tree = LazySegTreeMulSum::<i64>::from_vec(vec![1, 2, 3, 4, 5]);
+
+        tree.update(1..4, 1);
+        assert_eq!(tree.pending_tag_count(), 0);
+        assert_eq!(tree.query(..), 15);
+    }
+
+    #[test]
+    fn test_mul_sum_multiply_by_zero() {
+        let mut tree = LazySegTreeMulSum::<i64>::from_vec(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+
+        tree.update(2..6, 0); // [3, 1, 0, 0, 0, 0, 2, 6]
+        assert_eq!(tree.query(..), 12);
+        assert_eq!(tree.query(2..6), 0);
+
+        // Later factors cannot revive zeroed elements
+        tree.update(.., 7);
+        assert_eq!(tree.query(..), 84);
+        assert_eq!(tree.query(3..5), 0);
+    }
+
+    #[test]
+    fn test_mul_sum_random_against_brute_force() {
+        let mut rng = rand::rng();
+        let size = 37;
+        let random_values = |rng: &mut rand::rngs::ThreadRng| -> Vec<i64> {
+            (0..size).map(|_| rng.random_range(-5..=5)).collect()
+        };
+        let mut values = random_values(&mut rng);
+        let mut tree = LazySegTreeMulSum::<i64>::from_slice(&values);
+
+        for _ in 0..300 {
+            let l = rng.random_range(0..=size);
+            let r = rng.random_range(l..=size);
+            if rng.random_bool(0.5) {
+                // Zero factors would wipe out the values for good; they are
+                // covered by `test_mul_sum_multiply_by_zero`
+                let k = [-2, -1, 1, 2][rng.random_range(0..4)];
+                tree.update(l..r, k);
+                for v in &mut values[l..r] {
+                    *v *= k;
+                }
+                // Keep values bounded so the brute force never overflows
+                if values.iter().any(|v| v.abs() > 1 << 40) {
+                    values = random_values(&mut rng);
+                    tree = LazySegTreeMulSum::<i64>::from_slice(&values);
+                }
+            } else {
+                assert_eq!(tree.query(l..r), values[l..r].iter().sum::<i64>());
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Lazy segment tree for optional range assignment updates and sum queries.
+//!
+//! Provides `LazySegTreeOptReplaceSum<T>`, where an update of `None` leaves
+//! the range as is and `Some(v)` assigns `v`.
+
+use crate::{LazySegTree, LazySegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::{Add, Mul};
+use num_traits::{ConstZero, NumCast};
+
+/// Specification for optional range assignment updates with sum queries.
+///
+/// Composing a `None` update onto a pending assignment keeps the assignment,
+/// so no-op updates can be interleaved freely with real ones.
+pub struct LazySegTreeOptReplaceSumSpec<T>(PhantomData<T>);
+
+impl<T> LazySegTreeSpec for LazySegTreeOptReplaceSumSpec<T>
+where
+    T: Clone + ConstZero + Add<Output = T> + NumCast + Mul<Output = T>,
+{
+    type T = T;
+    type U = Option<T>;
+
+    const ID: Self::T = <T as ConstZero>::ZERO;
+
+    fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+        *d1 = d1.clone() + d2.clone();
+    }
+
+    fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+        if u2.is_some() {
+            *u1 = u2.clone();
+        }
+    }
+
+    fn op_update_on_data(u: &Self::U, d: &mut Self::T, size: usize) {
+        if let Some(v) = u {
+            let size = T::from(size).unwrap_or_else(|| panic!("Failed to convert usize to T"));
+            *d = v.clone() * size;
+        }
+    }
+
+    fn is_identity_update(u: &Self::U) -> bool {
+        u.is_none()
+    }
+}
+
+/// Convenience alias: a `LazySegTree` specialized for optional range
+/// assignment updates and sum queries.
+///
+/// # Examples
+///
+/// ```
+/// use array_range_query::LazySegTreeOptReplaceSum;
+///
+/// let mut tree = LazySegTreeOptReplaceSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+///
+/// tree.update(1..4, Some(10));
+/// assert_eq!(tree.query(..), 1 + 10 + 10 + 10 + 5);
+///
+/// // `None` leaves the range unchanged
+/// tree.update(.., None);
+/// assert_eq!(tree.query(..), 36);
+/// ```
+pub type LazySegTreeOptReplaceSum<T> = LazySegTree<LazySegTreeOptReplaceSumSpec<T>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleaved_noop_and_assign() {
+        let mut values = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+        let mut tree = LazySegTreeOptReplaceSum::<i64>::from_vec(values.clone());
+
+        let updates = [
+            (2..7, Some(8)),
+            (0..10, None),
+            (5..9, Some(-1)),
+            (3..6, None),
+            (0..4, Some(2)),
+            (1..10, None),
+            (4..5, Some(0)),
+        ];
+        for (range, update) in updates {
+            tree.update(range.clone(), update);
+            if let Some(v) = update {
+                values[range].fill(v);
+            }
+
+            for l in 0..=values.len() {
+                for r in l..=values.len() {
+                    assert_eq!(tree.query(l..r), values[l..r].iter().sum::<i64>());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_noop_composes_onto_pending_assign() {
+        type Spec = LazySegTreeOptReplaceSumSpec<i32>;
+
+        let mut tag = Some(5);
+        Spec::op_on_update(&mut tag, &None);
+        assert_eq!(tag, Some(5));
+        Spec::op_on_update(&mut tag, &Some(7));
+        assert_eq!(tag, Some(7));
+
+        let mut sum = 11;
+        Spec::op_update_on_data(&None, &mut sum, 3);
+        assert_eq!(sum, 11);
+        Spec::op_update_on_data(&tag, &mut sum, 3);
+        assert_eq!(sum, 21);
+    }
+
+    #[test]
+    fn test_noop_update_writes_no_tags() {
+        let mut tree = LazySegTreeOptReplaceSum::<i32>::new(8);
+        tree.update(1..7, None);
+        assert_eq!(tree.pending_tag_count(), 0);
+        tree.update(1..7, Some(2));
+        assert_eq!(tree.query(..), 12);
+    }
+}
@@ -3,9 +3,9 @@
 //! Provides `LazySegTreeReplaceSum<T>` for efficient range replacement with sum aggregation.
 
 use crate::{LazySegTree, LazySegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::{Add, Mul};
 use num_traits::{ConstZero, NumCast};
-use std::marker::PhantomData;
-use std::ops::{Add, Mul};
 
 /// Specification for range assignment (replace) updates with sum queries.
 pub struct LazySegTreeReplaceSumSpec<T>(PhantomData<T>);
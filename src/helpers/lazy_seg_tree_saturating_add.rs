@@ -0,0 +1,288 @@
+//! Lazy segment trees for saturating range add updates with min or max queries.
+//!
+//! Provides `LazySegTreeSaturatingAddMin<T>` and `LazySegTreeSaturatingAddMax<T>`,
+//! variants of [`LazySegTreeAddMin`](super::LazySegTreeAddMin) and
+//! [`LazySegTreeAddMax`](super::LazySegTreeAddMax) that clamp at the bounds of
+//! `T` instead of overflowing, so trees built with `new` accept range adds.
+//!
+//! Each element behaves as if every add were applied to it one at a time,
+//! saturating after each step, no matter how far pending tags have been
+//! pushed down.
+
+use super::Widen;
+use crate::{LazySegTree, LazySegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
+use min_max_traits::{Max as ConstUpperBound, Min as ConstLowerBound};
+use num_traits::{NumCast, SaturatingAdd, ToPrimitive};
+
+/// Pending saturating adds, kept exactly as `x ↦ clamp(x + add, lo, hi)`.
+///
+/// A sequence of saturating adds always composes into this form, so tags
+/// can be merged without losing what was clamped along the way. `add` is
+/// summed in `T::Wide`.
+#[derive(Clone, Debug)]
+pub struct SaturatingAddTag<T: Widen> {
+    add: T::Wide,
+    lo: T,
+    hi: T,
+}
+
+impl<T> SaturatingAddTag<T>
+where
+    T: Widen + NumCast + ConstLowerBound + ConstUpperBound + Ord,
+    T::Wide: SaturatingAdd + ToPrimitive + Ord,
+{
+    /// Creates the tag for a single saturating add of `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            add: value.widen(),
+            lo: T::MIN,
+            hi: T::MAX,
+        }
+    }
+
+    fn apply(&self, x: T) -> T {
+        let sum = x.widen().saturating_add(&self.add);
+        if sum <= self.lo.widen() {
+            self.lo
+        } else if sum >= self.hi.widen() {
+            self.hi
+        } else {
+            <T as NumCast>::from(sum).expect("sum lies strictly between two values of T")
+        }
+    }
+
+    /// Composes `next` after `self`.
+    fn then(&mut self, next: &Self) {
+        self.lo = next.apply(self.lo);
+        self.hi = next.apply(self.hi);
+        self.add = self.add.saturating_add(&next.add);
+    }
+}
+
+macro_rules! saturating_add_spec {
+    (
+        $(#[$spec_meta:meta])*
+        spec: $spec:ident,
+        $(#[$alias_meta:meta])*
+        alias: $alias:ident,
+        id: $id:ident,
+        keep: $keep:tt,
+    ) => {
+        $(#[$spec_meta])*
+        pub struct $spec<T>(PhantomData<T>);
+
+        impl<T> LazySegTreeSpec for $spec<T>
+        where
+            T: Widen + NumCast + ConstLowerBound + ConstUpperBound + Ord,
+            T::Wide: SaturatingAdd + ToPrimitive + Ord,
+        {
+            type T = T;
+            type U = SaturatingAddTag<T>;
+
+            const ID: Self::T = T::$id;
+
+            fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+                if *d2 $keep *d1 {
+                    *d1 = *d2;
+                }
+            }
+
+            fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+                u1.then(u2);
+            }
+
+            fn op_update_on_data(u: &Self::U, d: &mut Self::T, _size: usize) {
+                // Saturating adds are monotone, so they commute with min and max
+                *d = u.apply(*d);
+            }
+        }
+
+        $(#[$alias_meta])*
+        pub type $alias<T> = LazySegTree<$spec<T>>;
+
+        impl<T> LazySegTree<$spec<T>>
+        where
+            T: Widen + NumCast + ConstLowerBound + ConstUpperBound + Ord,
+            T::Wide: SaturatingAdd + ToPrimitive + Ord,
+        {
+            /// Adds `value` to every element in `range`, saturating at the
+            /// bounds of `T`.
+            ///
+            /// # Time Complexity
+            /// O(log n)
+            ///
+            /// # Panics
+            /// Panics if the range is invalid or out of bounds.
+            pub fn add<R: RangeBounds<usize>>(&mut self, range: R, value: T) {
+                self.update(range, SaturatingAddTag::new(value));
+            }
+        }
+    };
+}
+
+saturating_add_spec! {
+    /// Specification for saturating range add updates with minimum queries.
+    ///
+    /// Adding to the identity `T::MAX` leaves it at `T::MAX`.
+    spec: LazySegTreeSaturatingAddMinSpec,
+    /// Convenience alias: a `LazySegTree` specialized for saturating range add
+    /// updates and min queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use array_range_query::LazySegTreeSaturatingAddMin;
+    ///
+    /// let mut tree = LazySegTreeSaturatingAddMin::<i32>::new(5);
+    /// tree.add(1..4, 10);
+    /// assert_eq!(tree.query(..), i32::MAX);
+    ///
+    /// tree.add(2..5, -10);
+    /// assert_eq!(tree.query(..), i32::MAX - 10);
+    /// assert_eq!(tree.query(..2), i32::MAX);
+    /// ```
+    alias: LazySegTreeSaturatingAddMin,
+    id: MAX,
+    keep: <,
+}
+
+saturating_add_spec! {
+    /// Specification for saturating range add updates with maximum queries.
+    ///
+    /// Adding to the identity `T::MIN` leaves it at `T::MIN`.
+    spec: LazySegTreeSaturatingAddMaxSpec,
+    /// Convenience alias: a `LazySegTree` specialized for saturating range add
+    /// updates and max queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use array_range_query::LazySegTreeSaturatingAddMax;
+    ///
+    /// let mut tree = LazySegTreeSaturatingAddMax::<i32>::new(5);
+    /// tree.add(1..4, -10);
+    /// assert_eq!(tree.query(..), i32::MIN);
+    ///
+    /// tree.add(2..5, 10);
+    /// assert_eq!(tree.query(..), i32::MIN + 10);
+    /// assert_eq!(tree.query(..2), i32::MIN);
+    /// ```
+    alias: LazySegTreeSaturatingAddMax,
+    id: MIN,
+    keep: >,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LazySegTreeAddMax, LazySegTreeAddMin};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_saturating_add_new_tree_accepts_updates() {
+        // Both overflow and panic with the plain add trees
+        let mut min_tree = LazySegTreeSaturatingAddMin::<i32>::new(5);
+        min_tree.add(1..4, 10);
+        assert_eq!(min_tree.query(..), i32::MAX);
+        assert_eq!(min_tree.query(2..2), i32::MAX);
+        min_tree.add(2..5, -7);
+        assert_eq!(min_tree.query(..2), i32::MAX);
+        assert_eq!(min_tree.query(..), i32::MAX - 7);
+
+        let mut max_tree = LazySegTreeSaturatingAddMax::<i32>::new(5);
+        max_tree.add(1..4, -10);
+        assert_eq!(max_tree.query(..), i32::MIN);
+        assert_eq!(max_tree.query(2..2), i32::MIN);
+        max_tree.add(2..5, 7);
+        assert_eq!(max_tree.query(..2), i32::MIN);
+        assert_eq!(max_tree.query(..), i32::MIN + 7);
+    }
+
+    #[test]
+    fn test_saturating_add_matches_plain_add_without_saturation() {
+        let values = vec![5, 2, 8, 1, 9, 3];
+        let mut sat_min = LazySegTreeSaturatingAddMin::<i32>::from_slice(&values);
+        let mut sat_max = LazySegTreeSaturatingAddMax::<i32>::from_slice(&values);
+        let mut min = LazySegTreeAddMin::<i32>::from_slice(&values);
+        let mut max = LazySegTreeAddMax::<i32>::from_slice(&values);
+
+        for (l, r, add) in [(1, 4, 2), (0, 6, -3), (2, 5, 7), (4, 6, -1)] {
+            sat_min.add(l..r, add);
+            sat_max.add(l..r, add);
+            min.update(l..r, add);
+            max.update(l..r, add);
+            for a in 0..=values.len() {
+                for b in a..=values.len() {
+                    assert_eq!(sat_min.query(a..b), min.query(a..b));
+                    assert_eq!(sat_max.query(a..b), max.query(a..b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_saturating_add_clamps_at_bounds() {
+        let mut min_tree = LazySegTreeSaturatingAddMin::<i8>::from_vec(vec![-100, 0, 100]);
+        min_tree.add(.., -100);
+        assert_eq!(min_tree.query(..1), i8::MIN);
+        assert_eq!(min_tree.query(2..), 0);
+
+        let mut max_tree = LazySegTreeSaturatingAddMax::<i8>::from_vec(vec![-100, 0, 100]);
+        max_tree.add(.., 100);
+        assert_eq!(max_tree.query(2..), i8::MAX);
+        assert_eq!(max_tree.query(..1), 0);
+    }
+
+    #[test]
+    fn test_saturating_add_tags_compose_exactly() {
+        let mut min_tree = LazySegTreeSaturatingAddMin::<i8>::from_vec(vec![-100; 4]);
+        let mut max_tree = LazySegTreeSaturatingAddMax::<i8>::from_vec(vec![-100; 4]);
+        for (range, add) in [(0..4, 100), (1..3, 100), (0..4, 100), (0..4, -100)] {
+            min_tree.add(range.clone(), add);
+            max_tree.add(range, add);
+        }
+        // -100 + 100 + 100 - 100 never leaves the range of i8
+        assert_eq!(min_tree.query(..1), 0);
+        assert_eq!(max_tree.query(3..), 0);
+        // -100 + 100 + 100 saturates at 127 before the final -100
+        assert_eq!(min_tree.query(1..3), 27);
+        assert_eq!(max_tree.query(..), 27);
+        assert_eq!(min_tree.query(..), 0);
+    }
+
+    #[test]
+    fn test_saturating_add_matches_stepwise_brute_force() {
+        let n = 13usize;
+        let mut values: Vec<i8> = (0..n)
+            .map(|i| (i * 37 % 200) as i16 - 100)
+            .map(|v| v as i8)
+            .collect();
+        let mut min_tree = LazySegTreeSaturatingAddMin::<i8>::from_slice(&values);
+        let mut max_tree = LazySegTreeSaturatingAddMax::<i8>::from_slice(&values);
+
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        for _ in 0..2000 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let a = ((state >> 33) % (n as u64 + 1)) as usize;
+            let b = ((state >> 17) % (n as u64 + 1)) as usize;
+            let (l, r) = (a.min(b), a.max(b));
+            if state & 1 == 0 {
+                let add = (((state >> 40) % 201) as i16 - 100) as i8;
+                min_tree.add(l..r, add);
+                max_tree.add(l..r, add);
+                for v in &mut values[l..r] {
+                    *v = v.saturating_add(add);
+                }
+            } else {
+                let expected_min = values[l..r].iter().copied().min().unwrap_or(i8::MAX);
+                let expected_max = values[l..r].iter().copied().max().unwrap_or(i8::MIN);
+                assert_eq!(min_tree.query(l..r), expected_min, "[{}, {})", l, r);
+                assert_eq!(max_tree.query(l..r), expected_max, "[{}, {})", l, r);
+            }
+        }
+    }
+}
@@ -2,20 +2,53 @@
 //!
 //! Pre-built implementations for sum, min, max queries and range operations.
 
+mod seg_tree_hash;
+mod seg_tree_matrix;
 mod seg_tree_max;
+mod seg_tree_max_count;
+mod seg_tree_max_subarray;
 mod seg_tree_min;
+mod seg_tree_min_count;
+mod seg_tree_min_with_index;
 mod seg_tree_sum;
+mod seg_tree_widening_sum;
 
 mod lazy_seg_tree_add_max;
 mod lazy_seg_tree_add_min;
 mod lazy_seg_tree_add_sum;
+mod lazy_seg_tree_assign_add_sum;
+mod lazy_seg_tree_checked_add_sum;
+mod lazy_seg_tree_mul_sum;
+mod lazy_seg_tree_opt_replace_sum;
 mod lazy_seg_tree_replace_sum;
+mod lazy_seg_tree_saturating_add;
 
-pub use seg_tree_max::SegTreeMax;
-pub use seg_tree_min::SegTreeMin;
-pub use seg_tree_sum::SegTreeSum;
+mod option_spec;
 
-pub use lazy_seg_tree_add_max::LazySegTreeAddMax;
-pub use lazy_seg_tree_add_min::LazySegTreeAddMin;
-pub use lazy_seg_tree_add_sum::LazySegTreeAddSum;
-pub use lazy_seg_tree_replace_sum::LazySegTreeReplaceSum;
+pub use seg_tree_hash::{RollingHash, SegTreeHash, SegTreeHashSpec};
+pub use seg_tree_matrix::{SegTreeMatrix, SegTreeMatrixSpec};
+pub use seg_tree_max::{SegTreeMax, SegTreeMaxSpec};
+pub use seg_tree_max_count::{SegTreeMaxCount, SegTreeMaxCountSpec};
+pub use seg_tree_max_subarray::{MaxSubarrayNode, MaxSubarraySpec, SegTreeMaxSubarray};
+pub use seg_tree_min::{SegTreeMin, SegTreeMinSpec};
+pub use seg_tree_min_count::{SegTreeMinCount, SegTreeMinCountSpec};
+pub use seg_tree_min_with_index::{SegTreeMinWithIndex, SegTreeMinWithIndexSpec};
+pub use seg_tree_sum::{SegTreeFloatSum, SegTreeFloatSumSpec, SegTreeSum, SegTreeSumSpec};
+pub use seg_tree_widening_sum::{SegTreeWideningSum, SegTreeWideningSumSpec, Widen};
+
+pub use lazy_seg_tree_add_max::{LazySegTreeAddMax, LazySegTreeAddMaxSpec};
+pub use lazy_seg_tree_add_min::{LazySegTreeAddMin, LazySegTreeAddMinSpec};
+pub use lazy_seg_tree_add_sum::{LazySegTreeAddSum, LazySegTreeAddSumSpec};
+pub use lazy_seg_tree_assign_add_sum::{
+    AssignAdd, LazySegTreeAssignAddSum, LazySegTreeAssignAddSumSpec,
+};
+pub use lazy_seg_tree_checked_add_sum::{LazySegTreeCheckedAddSum, LazySegTreeCheckedAddSumSpec};
+pub use lazy_seg_tree_mul_sum::{LazySegTreeMulSum, LazySegTreeMulSumSpec};
+pub use lazy_seg_tree_opt_replace_sum::{LazySegTreeOptReplaceSum, LazySegTreeOptReplaceSumSpec};
+pub use lazy_seg_tree_replace_sum::{LazySegTreeReplaceSum, LazySegTreeReplaceSumSpec};
+pub use lazy_seg_tree_saturating_add::{
+    LazySegTreeSaturatingAddMax, LazySegTreeSaturatingAddMaxSpec, LazySegTreeSaturatingAddMin,
+    LazySegTreeSaturatingAddMinSpec, SaturatingAddTag,
+};
+
+pub use option_spec::{OptionSpec, SemigroupSpec};
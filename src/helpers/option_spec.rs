@@ -0,0 +1,133 @@
+//! Identity-free specs lifted into `Option`.
+//!
+//! Provides `OptionSpec<Inner>`, which turns an associative operation without
+//! an identity (a [`SemigroupSpec`]) into a full [`SegTreeSpec`] by using
+//! `None` as the identity.
+
+use crate::SegTreeSpec;
+use core::marker::PhantomData;
+
+/// Specification for an associative operation that has no identity element.
+///
+/// # Example
+/// ```rust
+/// use array_range_query::helpers::SemigroupSpec;
+///
+/// struct MaxStr;
+/// impl SemigroupSpec for MaxStr {
+///     type T = String;
+///     fn op(a: &mut Self::T, b: &Self::T) {
+///         if *a < *b {
+///             a.clone_from(b);
+///         }
+///     }
+/// }
+/// ```
+pub trait SemigroupSpec {
+    /// Element type combined by the operation.
+    type T: Clone;
+
+    /// Associative binary operation, performed in-place.
+    ///
+    /// Modifies `a` to store the result of combining `a` with `b`.
+    fn op(a: &mut Self::T, b: &Self::T);
+}
+
+/// Specification adapting a [`SemigroupSpec`] to `Option<Inner::T>`.
+///
+/// `None` is the identity: combining it with anything yields the other
+/// operand, and present values are combined with `Inner::op`. Queries over
+/// an empty range therefore return `None`.
+///
+/// # Examples
+///
+/// ```rust
+/// use array_range_query::helpers::{OptionSpec, SemigroupSpec};
+/// use array_range_query::SegTree;
+///
+/// struct MaxStr;
+/// impl SemigroupSpec for MaxStr {
+///     type T = String;
+///     fn op(a: &mut Self::T, b: &Self::T) {
+///         if *a < *b {
+///             a.clone_from(b);
+///         }
+///     }
+/// }
+///
+/// let names = ["kiwi", "apple", "pear"].map(|s| Some(s.to_string()));
+/// let tree = SegTree::<OptionSpec<MaxStr>>::from_slice(&names);
+/// assert_eq!(tree.query(..2).as_deref(), Some("kiwi"));
+/// assert_eq!(tree.query(1..).as_deref(), Some("pear"));
+/// assert_eq!(tree.query(1..1), None);
+/// ```
+pub struct OptionSpec<Inner>(PhantomData<Inner>);
+
+impl<Inner: SemigroupSpec> SegTreeSpec for OptionSpec<Inner> {
+    type T = Option<Inner::T>;
+    const ID: Self::T = None;
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        match (a.as_mut(), b) {
+            (Some(x), Some(y)) => Inner::op(x, y),
+            (None, Some(y)) => *a = Some(y.clone()),
+            (_, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SegTree;
+
+    /// Ordered type without a `MIN` constant.
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Version(u32, String);
+
+    struct MaxVersion;
+    impl SemigroupSpec for MaxVersion {
+        type T = Version;
+        fn op(a: &mut Self::T, b: &Self::T) {
+            if *a < *b {
+                *a = b.clone();
+            }
+        }
+    }
+
+    /// Non-commutative inner op, to check operand order is preserved.
+    struct First;
+    impl SemigroupSpec for First {
+        type T = char;
+        fn op(_a: &mut Self::T, _b: &Self::T) {}
+    }
+
+    #[test]
+    fn test_option_spec_max_without_min_constant() {
+        let versions: Vec<Option<Version>> = [(1, "b"), (3, "a"), (2, "z"), (3, "c")]
+            .iter()
+            .map(|&(n, s)| Some(Version(n, s.to_string())))
+            .collect();
+        let mut tree = SegTree::<OptionSpec<MaxVersion>>::from_slice(&versions);
+
+        assert_eq!(tree.query(..), Some(Version(3, "c".to_string())));
+        assert_eq!(tree.query(..3), Some(Version(3, "a".to_string())));
+        assert_eq!(tree.query(2..3), Some(Version(2, "z".to_string())));
+        assert_eq!(tree.query(2..2), None);
+
+        tree.update(3, None);
+        assert_eq!(tree.query(2..), Some(Version(2, "z".to_string())));
+        assert_eq!(tree.query(3..), None);
+    }
+
+    #[test]
+    fn test_option_spec_none_is_identity() {
+        let values = [None, Some('x'), None, Some('y'), None];
+        let tree = SegTree::<OptionSpec<First>>::from_slice(&values);
+
+        assert_eq!(tree.query(..), Some('x'));
+        assert_eq!(tree.query(2..), Some('y'));
+        assert_eq!(tree.query(..1), None);
+        assert_eq!(SegTree::<OptionSpec<First>>::new(4).query(..), None);
+    }
+}
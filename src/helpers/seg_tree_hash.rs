@@ -0,0 +1,173 @@
+//! Segment tree for polynomial rolling hashes of substrings.
+//!
+//! Provides `SegTreeHash` for O(log n) substring hashing with point updates.
+
+use crate::{SegTree, SegTreeSpec};
+
+/// Modulus of the hash, the Mersenne prime `2^61 - 1`.
+const MODULUS: u64 = (1 << 61) - 1;
+/// Base of the polynomial hash.
+const BASE: u64 = 131_542_391;
+
+/// Multiplies modulo `2^61 - 1` without overflow.
+fn mul_mod(a: u64, b: u64) -> u64 {
+    let product = a as u128 * b as u128;
+    let folded = (product & MODULUS as u128) as u64 + (product >> 61) as u64;
+    if folded >= MODULUS {
+        folded - MODULUS
+    } else {
+        folded
+    }
+}
+
+/// Polynomial hash of a segment, `s[0]·B^(len-1) + … + s[len-1]` mod `2^61 - 1`.
+///
+/// Along with the hash it stores `B^len`, so two segments combine in O(1)
+/// without a separate table of powers. Equal substrings always produce equal
+/// values; unequal ones collide with negligible probability.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RollingHash {
+    /// Hash of the segment.
+    pub hash: u64,
+    /// `BASE^len` modulo the prime.
+    pub base_pow: u64,
+    /// Number of symbols in the segment.
+    pub len: usize,
+}
+
+impl RollingHash {
+    /// Hash of the empty segment.
+    pub const EMPTY: Self = Self {
+        hash: 0,
+        base_pow: 1,
+        len: 0,
+    };
+
+    /// Hash of a single symbol.
+    pub fn symbol(value: u64) -> Self {
+        Self {
+            // Offset by one so a zero symbol differs from the empty segment
+            hash: (value % MODULUS + 1) % MODULUS,
+            base_pow: BASE,
+            len: 1,
+        }
+    }
+}
+
+impl From<char> for RollingHash {
+    fn from(c: char) -> Self {
+        Self::symbol(c as u64)
+    }
+}
+
+impl From<u8> for RollingHash {
+    fn from(b: u8) -> Self {
+        Self::symbol(b as u64)
+    }
+}
+
+/// Specification for concatenating rolling hashes (a non-commutative monoid).
+pub struct SegTreeHashSpec;
+
+impl SegTreeSpec for SegTreeHashSpec {
+    type T = RollingHash;
+    const ID: Self::T = RollingHash::EMPTY;
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        let shifted = mul_mod(a.hash, b.base_pow) + b.hash;
+        a.hash = if shifted >= MODULUS {
+            shifted - MODULUS
+        } else {
+            shifted
+        };
+        a.base_pow = mul_mod(a.base_pow, b.base_pow);
+        a.len += b.len;
+    }
+}
+
+/// Segment tree specialized for substring hashing.
+///
+/// # Examples
+///
+/// ```rust
+/// use array_range_query::helpers::{RollingHash, SegTreeHash};
+///
+/// let text = "abcabc";
+/// let mut tree = SegTreeHash::from_vec(text.chars().map(RollingHash::from).collect());
+///
+/// assert_eq!(tree.query(0..3), tree.query(3..6)); // "abc" == "abc"
+/// tree.update(4, RollingHash::from('x'));
+/// assert_ne!(tree.query(0..3), tree.query(3..6)); // "abc" != "axc"
+/// ```
+pub type SegTreeHash = SegTree<SegTreeHashSpec>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn hash_of(s: &str) -> RollingHash {
+        SegTreeHash::from_vec(s.bytes().map(RollingHash::from).collect()).query(..)
+    }
+
+    #[test]
+    fn test_hash_equal_and_unequal_substrings() {
+        let tree = SegTreeHash::from_vec("abracadabra".bytes().map(RollingHash::from).collect());
+
+        assert_eq!(tree.query(0..4), tree.query(7..11)); // "abra"
+        assert_eq!(tree.query(0..4), hash_of("abra"));
+        assert_ne!(tree.query(0..4), tree.query(1..5)); // "abra" vs "brac"
+        assert_ne!(tree.query(0..2), tree.query(1..3)); // "ab" vs "br"
+        assert_eq!(tree.query(3..3), RollingHash::EMPTY);
+        assert_eq!(tree.query(..).len, 11);
+    }
+
+    #[test]
+    fn test_hash_order_matters() {
+        assert_ne!(hash_of("ab"), hash_of("ba"));
+        assert_ne!(hash_of("\0"), hash_of(""));
+        assert_ne!(hash_of("\0a"), hash_of("a"));
+    }
+
+    #[test]
+    fn test_hash_after_point_edits() {
+        let mut text: Vec<u8> = b"xyzxyzxyz".to_vec();
+        let mut tree = SegTreeHash::from_vec(text.iter().map(|&b| RollingHash::from(b)).collect());
+        assert_eq!(tree.query(0..3), tree.query(6..9));
+
+        tree.update(7, RollingHash::from(b'q'));
+        text[7] = b'q';
+        assert_ne!(tree.query(0..3), tree.query(6..9));
+        assert_eq!(tree.query(6..9), hash_of("xqz"));
+
+        tree.update(1, RollingHash::from(b'q'));
+        text[1] = b'q';
+        assert_eq!(tree.query(0..3), tree.query(6..9));
+        assert_eq!(
+            tree.query(..),
+            hash_of(core::str::from_utf8(&text).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_hash_random_against_direct_comparison() {
+        let mut rng = rand::rng();
+        let size = 60;
+        let mut text: Vec<u8> = (0..size).map(|_| rng.random_range(b'a'..=b'b')).collect();
+        let mut tree = SegTreeHash::from_vec(text.iter().map(|&b| RollingHash::from(b)).collect());
+
+        for _ in 0..500 {
+            if rng.random_bool(0.3) {
+                let i = rng.random_range(0..size);
+                text[i] = rng.random_range(b'a'..=b'b');
+                tree.update(i, RollingHash::from(text[i]));
+            } else {
+                let len = rng.random_range(0..=8);
+                let a = rng.random_range(0..=size - len);
+                let b = rng.random_range(0..=size - len);
+                let same = text[a..a + len] == text[b..b + len];
+                assert_eq!(tree.query(a..a + len) == tree.query(b..b + len), same);
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Segment tree for products of small square matrices.
+//!
+//! Provides `SegTreeMatrix<K, M>` for range products of `K × K` matrices
+//! modulo `M`, e.g. to compose linear-recurrence transitions with point
+//! updates.
+
+use crate::{SegTree, SegTreeSpec};
+
+/// Specification for `K × K` matrix multiplication modulo `M`.
+///
+/// The identity matrix is `ID`. Matrix multiplication is not commutative, so
+/// `query(l..r)` returns exactly `A[l] · A[l+1] · … · A[r-1]`. Entries are
+/// reduced into `[0, M)` and products are accumulated in `i128`.
+pub struct SegTreeMatrixSpec<const K: usize, const M: i64>;
+
+impl<const K: usize, const M: i64> SegTreeSpec for SegTreeMatrixSpec<K, M> {
+    type T = [[i64; K]; K];
+
+    const ID: Self::T = {
+        let mut identity = [[0; K]; K];
+        let mut i = 0;
+        while i < K {
+            identity[i][i] = 1;
+            i += 1;
+        }
+        identity
+    };
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        let mut product = [[0; K]; K];
+        for (row, a_row) in product.iter_mut().zip(a.iter()) {
+            for (j, cell) in row.iter_mut().enumerate() {
+                let sum = a_row
+                    .iter()
+                    .zip(b.iter())
+                    .map(|(&x, b_row)| x as i128 * b_row[j] as i128)
+                    .sum::<i128>();
+                *cell = sum.rem_euclid(M as i128) as i64;
+            }
+        }
+        *a = product;
+    }
+}
+
+/// Segment tree specialized for `K × K` matrix products modulo `M`.
+///
+/// # Examples
+///
+/// ```rust
+/// use array_range_query::helpers::SegTreeMatrix;
+///
+/// const MOD: i64 = 1_000_000_007;
+/// let step = [[1, 1], [1, 0]]; // Fibonacci transition
+/// let tree = SegTreeMatrix::<2, MOD>::from_vec(vec![step; 10]);
+///
+/// // step^10 = [[F(11), F(10)], [F(10), F(9)]]
+/// assert_eq!(tree.query(..), [[89, 55], [55, 34]]);
+/// ```
+pub type SegTreeMatrix<const K: usize, const M: i64> = SegTree<SegTreeMatrixSpec<K, M>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    const MOD: i64 = 998_244_353;
+    type Spec2 = SegTreeMatrixSpec<2, MOD>;
+
+    fn fold(matrices: &[[[i64; 2]; 2]]) -> [[i64; 2]; 2] {
+        let mut acc = Spec2::ID;
+        for m in matrices {
+            Spec2::op(&mut acc, m);
+        }
+        acc
+    }
+
+    #[test]
+    fn test_matrix_identity() {
+        assert_eq!(Spec2::ID, [[1, 0], [0, 1]]);
+        assert_eq!(
+            SegTreeMatrixSpec::<3, MOD>::ID,
+            [[1, 0, 0], [0, 1, 0], [0, 0, 1]]
+        );
+        assert_eq!(SegTreeMatrix::<2, MOD>::new(4).query(..), Spec2::ID);
+    }
+
+    #[test]
+    fn test_matrix_order_is_preserved() {
+        let a = [[1, 1], [0, 1]];
+        let b = [[1, 0], [1, 1]];
+        let tree = SegTreeMatrix::<2, MOD>::from_vec(vec![a, b]);
+
+        assert_eq!(tree.query(..), [[2, 1], [1, 1]]); // a · b
+        assert_ne!(tree.query(..), fold(&[b, a])); // b · a = [[1, 1], [1, 2]]
+    }
+
+    #[test]
+    fn test_matrix_range_product_against_left_fold() {
+        let mut rng = rand::rng();
+        let size = 40;
+        let mut matrices: Vec<[[i64; 2]; 2]> = (0..size)
+            .map(|_| {
+                [
+                    [rng.random_range(0..MOD), rng.random_range(0..MOD)],
+                    [rng.random_range(0..MOD), rng.random_range(0..MOD)],
+                ]
+            })
+            .collect();
+        let mut tree = SegTreeMatrix::<2, MOD>::from_slice(&matrices);
+
+        for _ in 0..300 {
+            if rng.random_bool(0.3) {
+                let i = rng.random_range(0..size);
+                matrices[i] = [[rng.random_range(0..MOD), 1], [1, 0]];
+                tree.update(i, matrices[i]);
+            } else {
+                let l = rng.random_range(0..=size);
+                let r = rng.random_range(l..=size);
+                assert_eq!(tree.query(l..r), fold(&matrices[l..r]));
+            }
+        }
+    }
+}
@@ -3,8 +3,9 @@
 //! Provides `SegTreeMax<T>` for efficient range maximum queries.
 
 use crate::{SegTree, SegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
 use min_max_traits::Min as ConstLowerBound;
-use std::marker::PhantomData;
 
 /// Specification for maximum operations.
 pub struct SegTreeMaxSpec<T>(PhantomData<T>);
@@ -21,11 +22,75 @@ where
             *a = b.clone();
         }
     }
+
+    fn op_owned(a: Self::T, b: Self::T) -> Self::T {
+        if a < b {
+            b
+        } else {
+            a
+        }
+    }
 }
 
 /// Segment tree specialized for maximum operations.
 pub type SegTreeMax<T> = SegTree<SegTreeMaxSpec<T>>;
 
+impl<T> SegTree<SegTreeMaxSpec<T>>
+where
+    T: Clone + ConstLowerBound + Ord,
+{
+    /// Returns the index and value of the maximum element in the range, or
+    /// `None` if the range is empty.
+    ///
+    /// Ties resolve to the smallest index.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use array_range_query::SegTreeMax;
+    ///
+    /// let tree = SegTreeMax::<i32>::from_vec(vec![3, 9, 4, 9, 1]);
+    /// assert_eq!(tree.query_argmax(..), Some((1, 9)));
+    /// assert_eq!(tree.query_argmax(2..), Some((3, 9)));
+    /// assert_eq!(tree.query_argmax(2..2), None);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query_argmax<R: RangeBounds<usize>>(&self, range: R) -> Option<(usize, T)> {
+        self.arg_best(range, |a, b| a > b)
+    }
+
+    /// Returns the smallest index `>= left` whose value is at least
+    /// `threshold`, or `None` if there is no such element.
+    ///
+    /// Subtrees whose maximum is below `threshold` are skipped, which makes
+    /// this a "find the next available slot" primitive.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use array_range_query::SegTreeMax;
+    ///
+    /// let tree = SegTreeMax::<i32>::from_vec(vec![1, 7, 3, 9, 2]);
+    /// assert_eq!(tree.find_first_ge(0, 5), Some(1));
+    /// assert_eq!(tree.find_first_ge(2, 5), Some(3));
+    /// assert_eq!(tree.find_first_ge(4, 5), None);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `left > len`.
+    pub fn find_first_ge(&self, left: usize, threshold: T) -> Option<usize> {
+        self.find_first(left.., |max| *max >= threshold)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,4 +233,50 @@ mod tests {
         tree.update(1, 0); // Change MAX to 0
         assert_eq!(tree.query(..), 1); // max(MIN, 0, 0, -1, 1) = 1
     }
+
+    #[test]
+    fn test_query_argmax() {
+        let values = vec![4, -2, 17, 8, 3, 11, 0];
+        let tree = SegTreeMax::<i32>::from_slice(&values);
+        assert_eq!(tree.query_argmax(..), Some((2, 17)));
+        assert_eq!(tree.query_argmax(3..), Some((5, 11)));
+        assert_eq!(tree.query_argmax(..2), Some((0, 4)));
+        assert_eq!(tree.query_argmax(4..4), None);
+
+        // Ties resolve to the leftmost index, also across canonical nodes.
+        // `max_by_key` keeps the last maximum, so scan right to left.
+        let values = [1, 5, 2, 5, 5, 0];
+        let tree = SegTreeMax::<i32>::from_slice(&values);
+        for l in 0..6 {
+            for r in l + 1..=6 {
+                let expected = (l..r)
+                    .rev()
+                    .max_by_key(|&i| values[i])
+                    .map(|i| (i, values[i]));
+                assert_eq!(tree.query_argmax(l..r), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_first_ge() {
+        let values = vec![3, 8, 1, 1, 6, 2, 9, 4, 5];
+        let tree = SegTreeMax::<i32>::from_slice(&values);
+        for left in 0..=values.len() {
+            for threshold in 0..11 {
+                let expected = (left..values.len()).find(|&i| values[i] >= threshold);
+                assert_eq!(tree.find_first_ge(left, threshold), expected);
+            }
+        }
+
+        // No qualifying element anywhere
+        assert_eq!(tree.find_first_ge(0, 10), None);
+        assert_eq!(SegTreeMax::<i32>::new(0).find_first_ge(0, i32::MIN), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_find_first_ge_past_end() {
+        SegTreeMax::<i32>::from_slice(&[1, 2]).find_first_ge(3, 0);
+    }
 }
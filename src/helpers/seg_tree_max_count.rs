@@ -0,0 +1,125 @@
+//! Segment tree for maximum-with-multiplicity queries.
+//!
+//! Provides `SegTreeMaxCount<T>`, answering "what is the maximum in this
+//! range and how many elements equal it".
+
+use crate::{SegTree, SegTreeSpec};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
+use min_max_traits::Min as ConstLowerBound;
+
+/// Specification for `(max_value, count)` pairs.
+///
+/// `op` keeps the larger value and adds the counts when both values are
+/// equal. The identity is `(T::MIN, 0)`, which never changes a count.
+pub struct SegTreeMaxCountSpec<T>(PhantomData<T>);
+
+impl<T> SegTreeSpec for SegTreeMaxCountSpec<T>
+where
+    T: Clone + ConstLowerBound + Ord,
+{
+    type T = (T, usize);
+    const ID: Self::T = (<T as ConstLowerBound>::MIN, 0);
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        match b.0.cmp(&a.0) {
+            core::cmp::Ordering::Greater => *a = b.clone(),
+            core::cmp::Ordering::Equal => a.1 += b.1,
+            core::cmp::Ordering::Less => {}
+        }
+    }
+}
+
+/// Convenience alias: a `SegTree` returning the maximum of a range together
+/// with the number of elements equal to it.
+///
+/// # Example
+///
+/// ```rust
+/// use array_range_query::helpers::SegTreeMaxCount;
+///
+/// let mut tree = SegTreeMaxCount::<i32>::from_values(&[5, 1, 4, 5, 3]);
+/// assert_eq!(tree.query_with_count(..), (5, 2));
+/// assert_eq!(tree.query_with_count(1..), (5, 1));
+///
+/// tree.set(1, 5);
+/// assert_eq!(tree.query_with_count(..), (5, 3));
+/// ```
+pub type SegTreeMaxCount<T> = SegTree<SegTreeMaxCountSpec<T>>;
+
+impl<T> SegTree<SegTreeMaxCountSpec<T>>
+where
+    T: Clone + ConstLowerBound + Ord,
+{
+    /// Creates a tree from plain values, each counted once.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_values(values: &[T]) -> Self {
+        Self::from_vec(values.iter().map(|v| (v.clone(), 1)).collect::<Vec<_>>())
+    }
+
+    /// Sets the value at `index`, counted once.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) {
+        self.update(index, (value, 1));
+    }
+
+    /// Returns the maximum of the range and how many elements equal it, or
+    /// `(T::MIN, 0)` for an empty range.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query_with_count<R: RangeBounds<usize>>(&self, range: R) -> (T, usize) {
+        self.query(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn brute_force(values: &[i32], l: usize, r: usize) -> (i32, usize) {
+        let max = values[l..r].iter().copied().max().unwrap_or(i32::MIN);
+        (max, values[l..r].iter().filter(|&&v| v == max).count())
+    }
+
+    #[test]
+    fn test_max_count_repeated_maxima() {
+        let tree = SegTreeMaxCount::<i32>::from_values(&[2, 9, 5, 9, 9, -7, 9]);
+        assert_eq!(tree.query_with_count(..), (9, 4));
+        assert_eq!(tree.query_with_count(2..5), (9, 2));
+        assert_eq!(tree.query_with_count(5..6), (-7, 1));
+        assert_eq!(tree.query_with_count(3..3), (i32::MIN, 0));
+    }
+
+    #[test]
+    fn test_max_count_against_brute_force() {
+        let mut rng = rand::rng();
+        let size = 40;
+        let mut values: Vec<i32> = (0..size).map(|_| rng.random_range(-3..3)).collect();
+        let mut tree = SegTreeMaxCount::<i32>::from_values(&values);
+
+        for _ in 0..300 {
+            if rng.random_bool(0.3) {
+                let i = rng.random_range(0..size);
+                values[i] = rng.random_range(-3..3);
+                tree.set(i, values[i]);
+            } else {
+                let l = rng.random_range(0..=size);
+                let r = rng.random_range(l..=size);
+                assert_eq!(tree.query_with_count(l..r), brute_force(&values, l, r));
+            }
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Segment tree for maximum subarray sum queries.
+//!
+//! Provides `SegTreeMaxSubarray<T>`, the classic "GSS" tree answering the
+//! best non-empty contiguous sum inside any range, with point updates.
+
+use crate::helpers::{OptionSpec, SemigroupSpec};
+use crate::SegTree;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::{Add, RangeBounds};
+
+/// Summary of a non-empty segment for maximum subarray queries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxSubarrayNode<T> {
+    /// Sum of the whole segment.
+    pub total: T,
+    /// Best sum of a non-empty prefix.
+    pub prefix: T,
+    /// Best sum of a non-empty suffix.
+    pub suffix: T,
+    /// Best sum of a non-empty contiguous subarray.
+    pub best: T,
+}
+
+impl<T: Copy> MaxSubarrayNode<T> {
+    /// Summary of a single element.
+    pub fn leaf(value: T) -> Self {
+        Self {
+            total: value,
+            prefix: value,
+            suffix: value,
+            best: value,
+        }
+    }
+}
+
+/// Specification combining [`MaxSubarrayNode`]s.
+///
+/// Non-empty segments have no identity, so the tree wraps it in
+/// [`OptionSpec`] and uses `None` for empty ranges.
+pub struct MaxSubarraySpec<T>(PhantomData<T>);
+
+impl<T> SemigroupSpec for MaxSubarraySpec<T>
+where
+    T: Copy + Ord + Add<Output = T>,
+{
+    type T = MaxSubarrayNode<T>;
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        *a = MaxSubarrayNode {
+            total: a.total + b.total,
+            prefix: a.prefix.max(a.total + b.prefix),
+            suffix: b.suffix.max(b.total + a.suffix),
+            best: a.best.max(b.best).max(a.suffix + b.prefix),
+        };
+    }
+}
+
+/// Segment tree answering maximum subarray sum queries with point updates.
+///
+/// # Examples
+///
+/// ```rust
+/// use array_range_query::SegTreeMaxSubarray;
+///
+/// let mut tree = SegTreeMaxSubarray::from_slice(&[2, -5, 3, -1, 4, -9]);
+/// assert_eq!(tree.query(..), 6); // 3 - 1 + 4
+/// assert_eq!(tree.query(..2), 2);
+/// tree.update(3, -10);
+/// assert_eq!(tree.query(..), 4);
+/// ```
+pub struct SegTreeMaxSubarray<T>
+where
+    T: Copy + Ord + Add<Output = T>,
+{
+    tree: SegTree<OptionSpec<MaxSubarraySpec<T>>>,
+}
+
+impl<T> SegTreeMaxSubarray<T>
+where
+    T: Copy + Ord + Add<Output = T>,
+{
+    // ===== CONSTRUCTORS =====
+
+    /// Creates a new tree from a slice of values.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_slice(values: &[T]) -> Self {
+        Self::from_vec(values.to_vec())
+    }
+
+    /// Creates a new tree from a vector of values.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_vec(values: Vec<T>) -> Self {
+        let leaves = values
+            .into_iter()
+            .map(|v| Some(MaxSubarrayNode::leaf(v)))
+            .collect();
+        Self {
+            tree: SegTree::from_vec(leaves),
+        }
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Returns the number of elements in the tree.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns `true` if the tree holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Returns the maximum sum of a non-empty contiguous subarray inside the
+    /// range. If every element is negative this is the largest element.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid, out of bounds, or empty.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> T {
+        self.query_node(range)
+            .expect("maximum subarray of an empty range")
+            .best
+    }
+
+    /// Returns the full summary of the range, or `None` if it is empty.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query_node<R: RangeBounds<usize>>(&self, range: R) -> Option<MaxSubarrayNode<T>> {
+        self.tree.query(range)
+    }
+
+    /// Sets the value at `index`.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn update(&mut self, index: usize, value: T) {
+        self.tree.update(index, Some(MaxSubarrayNode::leaf(value)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn brute_force(values: &[i64]) -> i64 {
+        let mut best = i64::MIN;
+        for l in 0..values.len() {
+            let mut sum = 0;
+            for &v in &values[l..] {
+                sum += v;
+                best = best.max(sum);
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_max_subarray_basic() {
+        let tree = SegTreeMaxSubarray::from_slice(&[-2i64, 1, -3, 4, -1, 2, 1, -5, 4]);
+        assert_eq!(tree.query(..), 6); // 4 - 1 + 2 + 1
+        assert_eq!(tree.query(..3), 1);
+        assert_eq!(tree.query(7..), 4);
+        assert_eq!(tree.query_node(2..2), None);
+    }
+
+    #[test]
+    fn test_max_subarray_all_negative() {
+        let values = [-8i64, -3, -6, -2, -5, -4];
+        let tree = SegTreeMaxSubarray::from_slice(&values);
+        assert_eq!(tree.query(..), -2);
+        assert_eq!(tree.query(..3), -3);
+        assert_eq!(tree.query(4..5), -5);
+    }
+
+    #[test]
+    fn test_max_subarray_random_against_brute_force() {
+        let mut rng = rand::rng();
+        let size = 30;
+        let mut values: Vec<i64> = (0..size).map(|_| rng.random_range(-20..20)).collect();
+        let mut tree = SegTreeMaxSubarray::from_slice(&values);
+
+        for _ in 0..300 {
+            if rng.random_bool(0.3) {
+                let i = rng.random_range(0..size);
+                values[i] = rng.random_range(-20..20);
+                tree.update(i, values[i]);
+            } else {
+                let l = rng.random_range(0..size);
+                let r = rng.random_range(l + 1..=size);
+                assert_eq!(tree.query(l..r), brute_force(&values[l..r]));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "empty range")]
+    fn test_panic_empty_range() {
+        let tree = SegTreeMaxSubarray::from_slice(&[1i64, 2, 3]);
+        tree.query(1..1);
+    }
+}
@@ -3,8 +3,9 @@
 //! Provides `SegTreeMin<T>` for efficient range minimum queries.
 
 use crate::{SegTree, SegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
 use min_max_traits::Max as ConstUpperBound;
-use std::marker::PhantomData;
 
 /// Specification for minimum operations.
 pub struct SegTreeMinSpec<T>(PhantomData<T>);
@@ -21,6 +22,14 @@ where
             *a = b.clone();
         }
     }
+
+    fn op_owned(a: Self::T, b: Self::T) -> Self::T {
+        if a > b {
+            b
+        } else {
+            a
+        }
+    }
 }
 
 /// Convenience alias: a `SegTree` specialized to perform minimum queries over `T`.
@@ -40,6 +49,36 @@ where
 /// ```
 pub type SegTreeMin<T> = SegTree<SegTreeMinSpec<T>>;
 
+impl<T> SegTree<SegTreeMinSpec<T>>
+where
+    T: Clone + ConstUpperBound + Ord,
+{
+    /// Returns the index and value of the minimum element in the range, or
+    /// `None` if the range is empty.
+    ///
+    /// Ties resolve to the smallest index.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use array_range_query::SegTreeMin;
+    ///
+    /// let tree = SegTreeMin::<i32>::from_vec(vec![3, 1, 4, 1, 5]);
+    /// assert_eq!(tree.query_argmin(..), Some((1, 1)));
+    /// assert_eq!(tree.query_argmin(2..), Some((3, 1)));
+    /// assert_eq!(tree.query_argmin(2..2), None);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query_argmin<R: RangeBounds<usize>>(&self, range: R) -> Option<(usize, T)> {
+        self.arg_best(range, |a, b| a < b)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,4 +228,24 @@ mod tests {
         let tree = SegTreeMin::<i32>::from_vec(values); // moves `values` into the tree
         assert_eq!(tree.query(..), 1);
     }
+
+    #[test]
+    fn test_query_argmin() {
+        let values = vec![4, -2, 17, 8, 3, -9, 0];
+        let tree = SegTreeMin::<i32>::from_slice(&values);
+        assert_eq!(tree.query_argmin(..), Some((5, -9)));
+        assert_eq!(tree.query_argmin(..5), Some((1, -2)));
+        assert_eq!(tree.query_argmin(2..4), Some((3, 8)));
+        assert_eq!(tree.query_argmin(4..4), None);
+
+        // Ties resolve to the leftmost index, also across canonical nodes
+        let values = [3, 0, 2, 0, 0, 7];
+        let tree = SegTreeMin::<i32>::from_slice(&values);
+        for l in 0..6 {
+            for r in l + 1..=6 {
+                let expected = (l..r).min_by_key(|&i| values[i]).map(|i| (i, values[i]));
+                assert_eq!(tree.query_argmin(l..r), expected);
+            }
+        }
+    }
 }
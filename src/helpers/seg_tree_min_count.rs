@@ -0,0 +1,125 @@
+//! Segment tree for minimum-with-multiplicity queries.
+//!
+//! Provides `SegTreeMinCount<T>`, answering "what is the minimum in this
+//! range and how many elements equal it".
+
+use crate::{SegTree, SegTreeSpec};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
+use min_max_traits::Max as ConstUpperBound;
+
+/// Specification for `(min_value, count)` pairs.
+///
+/// `op` keeps the smaller value and adds the counts when both values are
+/// equal. The identity is `(T::MAX, 0)`, which never changes a count.
+pub struct SegTreeMinCountSpec<T>(PhantomData<T>);
+
+impl<T> SegTreeSpec for SegTreeMinCountSpec<T>
+where
+    T: Clone + ConstUpperBound + Ord,
+{
+    type T = (T, usize);
+    const ID: Self::T = (<T as ConstUpperBound>::MAX, 0);
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        match b.0.cmp(&a.0) {
+            core::cmp::Ordering::Less => *a = b.clone(),
+            core::cmp::Ordering::Equal => a.1 += b.1,
+            core::cmp::Ordering::Greater => {}
+        }
+    }
+}
+
+/// Convenience alias: a `SegTree` returning the minimum of a range together
+/// with the number of elements equal to it.
+///
+/// # Example
+///
+/// ```rust
+/// use array_range_query::helpers::SegTreeMinCount;
+///
+/// let mut tree = SegTreeMinCount::<i32>::from_values(&[3, 1, 4, 1, 5]);
+/// assert_eq!(tree.query_with_count(..), (1, 2));
+/// assert_eq!(tree.query_with_count(2..), (1, 1));
+///
+/// tree.set(0, 1);
+/// assert_eq!(tree.query_with_count(..), (1, 3));
+/// ```
+pub type SegTreeMinCount<T> = SegTree<SegTreeMinCountSpec<T>>;
+
+impl<T> SegTree<SegTreeMinCountSpec<T>>
+where
+    T: Clone + ConstUpperBound + Ord,
+{
+    /// Creates a tree from plain values, each counted once.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_values(values: &[T]) -> Self {
+        Self::from_vec(values.iter().map(|v| (v.clone(), 1)).collect::<Vec<_>>())
+    }
+
+    /// Sets the value at `index`, counted once.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) {
+        self.update(index, (value, 1));
+    }
+
+    /// Returns the minimum of the range and how many elements equal it, or
+    /// `(T::MAX, 0)` for an empty range.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query_with_count<R: RangeBounds<usize>>(&self, range: R) -> (T, usize) {
+        self.query(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn brute_force(values: &[i32], l: usize, r: usize) -> (i32, usize) {
+        let min = values[l..r].iter().copied().min().unwrap_or(i32::MAX);
+        (min, values[l..r].iter().filter(|&&v| v == min).count())
+    }
+
+    #[test]
+    fn test_min_count_repeated_minima() {
+        let tree = SegTreeMinCount::<i32>::from_values(&[2, 0, 5, 0, 0, 7, 0]);
+        assert_eq!(tree.query_with_count(..), (0, 4));
+        assert_eq!(tree.query_with_count(2..5), (0, 2));
+        assert_eq!(tree.query_with_count(5..6), (7, 1));
+        assert_eq!(tree.query_with_count(3..3), (i32::MAX, 0));
+    }
+
+    #[test]
+    fn test_min_count_against_brute_force() {
+        let mut rng = rand::rng();
+        let size = 40;
+        let mut values: Vec<i32> = (0..size).map(|_| rng.random_range(-3..3)).collect();
+        let mut tree = SegTreeMinCount::<i32>::from_values(&values);
+
+        for _ in 0..300 {
+            if rng.random_bool(0.3) {
+                let i = rng.random_range(0..size);
+                values[i] = rng.random_range(-3..3);
+                tree.set(i, values[i]);
+            } else {
+                let l = rng.random_range(0..=size);
+                let r = rng.random_range(l..=size);
+                assert_eq!(tree.query_with_count(l..r), brute_force(&values, l, r));
+            }
+        }
+    }
+}
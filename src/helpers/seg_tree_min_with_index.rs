@@ -0,0 +1,162 @@
+//! Segment tree for minimum-with-position queries.
+//!
+//! Provides `SegTreeMinWithIndex<T>`, answering "what is the minimum in this
+//! range and where is it".
+
+use crate::{SegTree, SegTreeSpec};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
+use min_max_traits::Max as ConstUpperBound;
+
+/// Specification for `(min_value, argmin_index)` pairs.
+///
+/// `op` keeps the smaller value; ties go to the smaller index. The identity
+/// is `(T::MAX, usize::MAX)`, which loses every comparison.
+pub struct SegTreeMinWithIndexSpec<T>(PhantomData<T>);
+
+impl<T> SegTreeSpec for SegTreeMinWithIndexSpec<T>
+where
+    T: Clone + ConstUpperBound + Ord,
+{
+    type T = (T, usize);
+    const ID: Self::T = (<T as ConstUpperBound>::MAX, usize::MAX);
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        if (&b.0, b.1) < (&a.0, a.1) {
+            *a = b.clone();
+        }
+    }
+}
+
+/// Segment tree returning the minimum of a range together with the index of
+/// its first occurrence.
+///
+/// The stored index must always match the element's position, so only
+/// operations that keep it in sync are exposed.
+///
+/// # Example
+///
+/// ```rust
+/// use array_range_query::helpers::SegTreeMinWithIndex;
+///
+/// let mut tree = SegTreeMinWithIndex::<i32>::from_values(&[5, 2, 8, 2, 9]);
+/// assert_eq!(tree.query(..), (2, 1));
+/// assert_eq!(tree.query(2..), (2, 3));
+///
+/// tree.set(4, 0);
+/// assert_eq!(tree.query(..), (0, 4));
+/// ```
+pub struct SegTreeMinWithIndex<T>
+where
+    T: Clone + ConstUpperBound + Ord,
+{
+    tree: SegTree<SegTreeMinWithIndexSpec<T>>,
+}
+
+impl<T> SegTreeMinWithIndex<T>
+where
+    T: Clone + ConstUpperBound + Ord,
+{
+    // ===== CONSTRUCTORS =====
+
+    /// Creates a tree from plain values, pairing each with its index.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_values(values: &[T]) -> Self {
+        Self {
+            tree: SegTree::from_vec(values.iter().cloned().zip(0..).collect::<Vec<_>>()),
+        }
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Returns the number of elements in the tree.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns `true` if the tree holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Returns the minimum of the range and the index of its first
+    /// occurrence, or `(T::MAX, usize::MAX)` if the range is empty.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> (T, usize) {
+        self.tree.query(range)
+    }
+
+    /// Sets the value at `index`, keeping the stored index in sync.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) {
+        self.tree.update(index, (value, index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn brute_force(values: &[i32], l: usize, r: usize) -> (i32, usize) {
+        (l..r).fold((i32::MAX, usize::MAX), |best, i| {
+            if values[i] < best.0 {
+                (values[i], i)
+            } else {
+                best
+            }
+        })
+    }
+
+    #[test]
+    fn test_min_with_index_ties_prefer_smaller_index() {
+        let tree = SegTreeMinWithIndex::<i32>::from_values(&[4, 1, 7, 1, 1, 3]);
+        assert_eq!(tree.query(..), (1, 1));
+        assert_eq!(tree.query(2..), (1, 3));
+        assert_eq!(tree.query(4..), (1, 4));
+        assert_eq!(tree.query(2..2), (i32::MAX, usize::MAX));
+    }
+
+    #[test]
+    fn test_min_with_index_set_keeps_positions() {
+        let mut tree = SegTreeMinWithIndex::<i32>::from_values(&[5, 2]);
+        assert_eq!(tree.len(), 2);
+        tree.set(0, 2);
+        tree.set(1, 5);
+        assert_eq!(tree.query(..), (2, 0));
+        assert!(SegTreeMinWithIndex::<i32>::from_values(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_min_with_index_against_brute_force() {
+        let mut rng = rand::rng();
+        let size = 40;
+        let mut values: Vec<i32> = (0..size).map(|_| rng.random_range(-10..10)).collect();
+        let mut tree = SegTreeMinWithIndex::<i32>::from_values(&values);
+
+        for _ in 0..300 {
+            if rng.random_bool(0.3) {
+                let i = rng.random_range(0..size);
+                values[i] = rng.random_range(-10..10);
+                tree.set(i, values[i]);
+            } else {
+                let l = rng.random_range(0..=size);
+                let r = rng.random_range(l..=size);
+                assert_eq!(tree.query(l..r), brute_force(&values, l, r));
+            }
+        }
+    }
+}
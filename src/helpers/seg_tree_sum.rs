@@ -1,13 +1,19 @@
 //! Segment tree for sum operations.
 //!
-//! Provides `SegTreeSum<T>` for efficient range sum queries.
+//! Provides `SegTreeSum<T>` for efficient range sum queries, and
+//! `SegTreeFloatSum<T>`, which also rejects NaN values in debug builds.
 
 use crate::{SegTree, SegTreeSpec};
+use core::marker::PhantomData;
+use core::ops::AddAssign;
+use num_traits::float::FloatCore;
 use num_traits::ConstZero;
-use std::marker::PhantomData;
-use std::ops::AddAssign;
 
 /// Specification for sum operations.
+///
+/// Values are not checked, so a single floating-point NaN silently poisons
+/// every aggregate above it. For `f32`/`f64` prefer
+/// [`SegTreeFloatSumSpec`], which rejects NaN in debug builds.
 pub struct SegTreeSumSpec<T>(PhantomData<T>);
 
 impl<T> SegTreeSpec for SegTreeSumSpec<T>
@@ -23,12 +29,122 @@ where
 }
 
 /// Segment tree specialized for sum operations.
+///
+/// For floating-point values use [`SegTreeFloatSum`] instead, which panics
+/// on NaN in debug builds.
 pub type SegTreeSum<T> = SegTree<SegTreeSumSpec<T>>;
 
+/// Specification for sums of floating-point values.
+///
+/// In debug builds, constructing a tree from or writing a NaN panics, since a
+/// single NaN would silently poison every aggregate above it.
+pub struct SegTreeFloatSumSpec<T>(PhantomData<T>);
+
+impl<T> SegTreeSpec for SegTreeFloatSumSpec<T>
+where
+    T: FloatCore + ConstZero + AddAssign<T>,
+{
+    type T = T;
+    const ID: Self::T = <T as ConstZero>::ZERO;
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        *a += *b;
+    }
+
+    fn debug_check(values: &[Self::T]) {
+        assert!(
+            !values.iter().any(|v| v.is_nan()),
+            "SegTreeFloatSum does not accept NaN values"
+        );
+    }
+}
+
+/// Segment tree specialized for floating-point sums.
+///
+/// # Example
+///
+/// ```rust
+/// use array_range_query::helpers::SegTreeFloatSum;
+///
+/// let mut tree = SegTreeFloatSum::<f64>::from_vec(vec![0.5, 1.25, -2.0]);
+/// tree.update(0, 4.0);
+/// assert_eq!(tree.query(..), 3.25);
+/// ```
+pub type SegTreeFloatSum<T> = SegTree<SegTreeFloatSumSpec<T>>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sum_floats() {
+        let mut tree = SegTreeSum::<f64>::from_vec(vec![0.5, 1.25, -2.0]);
+        tree.update(0, 4.0);
+        assert_eq!(tree.query(..), 3.25);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "SegTreeFloatSum does not accept NaN values")]
+    fn test_float_sum_rejects_nan_on_construction() {
+        SegTreeFloatSum::<f64>::from_vec(vec![1.0, f64::NAN, 2.0]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "SegTreeFloatSum does not accept NaN values")]
+    fn test_float_sum_rejects_nan_on_update() {
+        let mut tree = SegTreeFloatSum::<f32>::from_vec(vec![1.0, 2.0, 3.0]);
+        tree.update(1, f32::NAN);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "SegTreeFloatSum does not accept NaN values")]
+    fn test_float_sum_rejects_nan_on_fill() {
+        let mut tree = SegTreeFloatSum::<f64>::new(3);
+        tree.fill(f64::NAN);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "SegTreeFloatSum does not accept NaN values")]
+    fn test_float_sum_rejects_nan_on_resize() {
+        let mut tree = SegTreeFloatSum::<f64>::from_vec(vec![1.0, 2.0]);
+        tree.resize(5, f64::NAN);
+    }
+
+    #[test]
+    fn test_sum_accepts_types_without_partial_eq() {
+        #[derive(Clone, Copy)]
+        struct Meters(i64);
+        impl core::ops::Add for Meters {
+            type Output = Self;
+            fn add(self, other: Self) -> Self {
+                Meters(self.0 + other.0)
+            }
+        }
+        impl num_traits::Zero for Meters {
+            fn zero() -> Self {
+                Meters(0)
+            }
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+        impl ConstZero for Meters {
+            const ZERO: Self = Meters(0);
+        }
+        impl AddAssign for Meters {
+            fn add_assign(&mut self, other: Self) {
+                self.0 += other.0;
+            }
+        }
+
+        let tree = SegTreeSum::<Meters>::from_vec(vec![Meters(3), Meters(4)]);
+        assert_eq!(tree.query(..).0, 7);
+    }
+
     #[test]
     fn test_sum_basic_operations() {
         let values = vec![1, 2, 3, 4, 5];
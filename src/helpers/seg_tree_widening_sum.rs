@@ -0,0 +1,121 @@
+//! Segment tree for sums accumulated in a wider integer type.
+//!
+//! Provides `SegTreeWideningSum<T>`, whose elements are `T` but whose stored
+//! aggregates are `T::Wide`, so range sums of many large values do not
+//! overflow.
+
+use crate::{SegTree, SegTreeSpec};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::AddAssign;
+use num_traits::ConstZero;
+
+/// Maps an integer type to the next wider one, losslessly.
+///
+/// A sum of up to `2^bits` values of a `bits`-wide type always fits in the
+/// wide type, e.g. any realistic number of `i64` values in `i128`.
+pub trait Widen: Copy {
+    /// The type sums are accumulated in.
+    type Wide: Clone + ConstZero + AddAssign<Self::Wide>;
+
+    /// Converts `self` to the wider type.
+    fn widen(self) -> Self::Wide;
+}
+
+macro_rules! impl_widen {
+    ($($narrow:ty => $wide:ty),* $(,)?) => {
+        $(
+            impl Widen for $narrow {
+                type Wide = $wide;
+
+                fn widen(self) -> Self::Wide {
+                    <$wide>::from(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_widen! {
+    i8 => i16, i16 => i32, i32 => i64, i64 => i128,
+    u8 => u16, u16 => u32, u32 => u64, u64 => u128,
+}
+
+/// Specification for sums of `T` stored as `T::Wide`.
+pub struct SegTreeWideningSumSpec<T>(PhantomData<T>);
+
+impl<T: Widen> SegTreeSpec for SegTreeWideningSumSpec<T> {
+    type T = T::Wide;
+    const ID: Self::T = <T::Wide as ConstZero>::ZERO;
+
+    fn op(a: &mut Self::T, b: &Self::T) {
+        *a += b.clone();
+    }
+}
+
+/// Convenience alias: a `SegTree` summing `T` values in `T::Wide`.
+///
+/// # Example
+///
+/// ```rust
+/// use array_range_query::helpers::SegTreeWideningSum;
+///
+/// let mut tree = SegTreeWideningSum::<i64>::from_values(&[i64::MAX, i64::MAX, 1]);
+/// assert_eq!(tree.query(..), 2 * i64::MAX as i128 + 1);
+///
+/// tree.set(2, -1);
+/// assert_eq!(tree.query(1..), i64::MAX as i128 - 1);
+/// ```
+pub type SegTreeWideningSum<T> = SegTree<SegTreeWideningSumSpec<T>>;
+
+impl<T: Widen> SegTree<SegTreeWideningSumSpec<T>> {
+    /// Creates a tree from narrow values, widening each one.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_values(values: &[T]) -> Self {
+        Self::from_vec(values.iter().map(|&v| v.widen()).collect::<Vec<_>>())
+    }
+
+    /// Sets the element at `index` to the widened `value`.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) {
+        self.update(index, value.widen());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sums_many_large_i64_without_overflow() {
+        let n = 1000;
+        let half = i64::MAX / 2;
+        let mut tree = SegTreeWideningSum::<i64>::from_values(&vec![half; n]);
+
+        assert_eq!(tree.query(..), half as i128 * n as i128);
+        assert_eq!(tree.query(10..20), half as i128 * 10);
+
+        tree.set(0, i64::MIN);
+        assert_eq!(tree.query(..1), i64::MIN as i128);
+        assert_eq!(
+            tree.query(..),
+            half as i128 * (n as i128 - 1) + i64::MIN as i128
+        );
+    }
+
+    #[test]
+    fn test_widening_narrow_types() {
+        let tree = SegTreeWideningSum::<u8>::from_values(&[200, 200, 200]);
+        assert_eq!(tree.query(..), 600u16);
+
+        let tree = SegTreeWideningSum::<i32>::from_values(&[i32::MIN; 4]);
+        assert_eq!(tree.query(1..), 3 * i32::MIN as i64);
+    }
+}
@@ -0,0 +1,187 @@
+//! Shared node logic for the lazy segment trees.
+//!
+//! [`LazySegTree`](crate::LazySegTree) keeps its nodes in `RefCell`s and
+//! [`CopyLazySegTree`](crate::CopyLazySegTree) stores them directly, but both
+//! use the same 1-based layout. [`LazyNodes`] borrows the two node arrays as
+//! plain slices and implements building, pushing, pulling, updating and
+//! folding once for both trees.
+
+use crate::{LazySegTreeSpec, SegTreeNode};
+
+/// Builds every internal node of `data` from its leaves, bottom up.
+pub(crate) fn build<Spec: LazySegTreeSpec>(data: &mut [Spec::T]) {
+    let max_size = data.len() / 2;
+    for i in (1..max_size).rev() {
+        let mut v = data[i * 2].clone();
+        Spec::op_on_data(&mut v, &data[i * 2 + 1]);
+        data[i] = v;
+    }
+}
+
+/// Returns the value of `node` with its own pending tag applied.
+pub(crate) fn eval<Spec: LazySegTreeSpec>(
+    data: &[Spec::T],
+    tags: &[Option<Spec::U>],
+    max_depth: u32,
+    node: SegTreeNode,
+) -> Spec::T {
+    let mut d = data[node.0].clone();
+    if let Some(tag) = &tags[node.0] {
+        Spec::op_update_on_data(tag, &mut d, node.size(max_depth));
+    }
+    d
+}
+
+/// Mutable view over the node and tag arrays of a lazy segment tree.
+pub(crate) struct LazyNodes<'a, Spec: LazySegTreeSpec> {
+    data: &'a mut [Spec::T],
+    tags: &'a mut [Option<Spec::U>],
+    max_depth: u32,
+}
+
+impl<'a, Spec: LazySegTreeSpec> LazyNodes<'a, Spec> {
+    pub(crate) fn new(
+        data: &'a mut [Spec::T],
+        tags: &'a mut [Option<Spec::U>],
+        max_depth: u32,
+    ) -> Self {
+        Self {
+            data,
+            tags,
+            max_depth,
+        }
+    }
+
+    /// Pushes the tags above the leaf range `[l, r)` and folds the nodes
+    /// covering it from left to right.
+    pub(crate) fn query(&mut self, l: usize, r: usize) -> Spec::T {
+        self.push_boundaries(l, r);
+        self.fold(l, r)
+    }
+
+    /// Tags the nodes covering the leaf range `[l, r)` with `value` and
+    /// recomputes their ancestors.
+    pub(crate) fn update(&mut self, mut l: usize, mut r: usize, value: Spec::U) {
+        self.push_boundaries(l, r);
+        let (l0, r0) = (l, r);
+
+        // Each canonical node is tagged one step late, so that the last one
+        // can take `value` by move and only the others need a clone.
+        let mut last = None;
+        while l < r {
+            if l & 1 != 0 {
+                if let Some(prev) = last.replace(l) {
+                    combine_tag::<Spec>(&mut self.tags[prev], &value);
+                }
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                if let Some(prev) = last.replace(r) {
+                    combine_tag::<Spec>(&mut self.tags[prev], &value);
+                }
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        if let Some(last) = last {
+            combine_tag_owned::<Spec>(&mut self.tags[last], value);
+        }
+
+        for i in 1..=self.max_depth {
+            if ((l0 >> i) << i) != l0 {
+                self.pull(SegTreeNode(l0 >> i));
+            }
+            if ((r0 >> i) << i) != r0 {
+                self.pull(SegTreeNode((r0 - 1) >> i));
+            }
+        }
+    }
+
+    /// Returns the value of `node` with its own pending tag applied.
+    pub(crate) fn eval(&self, node: SegTreeNode) -> Spec::T {
+        eval::<Spec>(self.data, self.tags, self.max_depth, node)
+    }
+
+    /// Applies the tag of `node` to it and hands the tag to its children.
+    #[inline]
+    pub(crate) fn push(&mut self, node: SegTreeNode) {
+        if let Some(tag) = self.tags[node.0].take() {
+            Spec::op_update_on_data(&tag, &mut self.data[node.0], node.size(self.max_depth));
+            if !node.is_leaf(self.max_depth) {
+                combine_tag::<Spec>(&mut self.tags[node.left_child().0], &tag);
+                combine_tag_owned::<Spec>(&mut self.tags[node.right_child().0], tag);
+            }
+        }
+    }
+
+    /// Recomputes `node` from its children.
+    fn pull(&mut self, node: SegTreeNode) {
+        if node.is_leaf(self.max_depth) {
+            return;
+        }
+        let mut res = self.eval(node.left_child());
+        Spec::op_on_data(&mut res, &self.eval(node.right_child()));
+        self.data[node.0] = res;
+    }
+
+    /// Pushes, root first, every proper ancestor of the leaf boundaries `l`
+    /// and `r` that does not start exactly at that boundary. These are the
+    /// nodes whose tags must be pushed before the nodes covering `[l, r)`
+    /// can be read or tagged.
+    fn push_boundaries(&mut self, l: usize, r: usize) {
+        for i in (1..=self.max_depth).rev() {
+            if ((l >> i) << i) != l {
+                self.push(SegTreeNode(l >> i));
+            }
+            if ((r >> i) << i) != r {
+                self.push(SegTreeNode((r - 1) >> i));
+            }
+        }
+    }
+
+    /// Folds the nodes covering the leaf range `[l, r)` from left to right,
+    /// keeping separate accumulators so non-commutative specs stay ordered.
+    fn fold(&self, mut l: usize, mut r: usize) -> Spec::T {
+        let mut result_left = Spec::ID;
+        let mut result_right = Spec::ID;
+
+        while l < r {
+            if l & 1 != 0 {
+                Spec::op_on_data(&mut result_left, &self.eval(SegTreeNode(l)));
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                let mut v = self.eval(SegTreeNode(r));
+                Spec::op_on_data(&mut v, &result_right);
+                result_right = v;
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+
+        Spec::op_on_data(&mut result_left, &result_right);
+        result_left
+    }
+}
+
+#[inline]
+fn combine_tag<Spec: LazySegTreeSpec>(existing_tag: &mut Option<Spec::U>, new_tag: &Spec::U) {
+    if let Some(existing) = existing_tag {
+        Spec::op_on_update(existing, new_tag);
+    } else {
+        *existing_tag = Some(new_tag.clone());
+    }
+}
+
+/// Like [`combine_tag`], but moves `new_tag` into an empty slot instead of
+/// cloning it.
+#[inline]
+fn combine_tag_owned<Spec: LazySegTreeSpec>(existing_tag: &mut Option<Spec::U>, new_tag: Spec::U) {
+    if let Some(existing) = existing_tag {
+        Spec::op_on_update(existing, &new_tag);
+    } else {
+        *existing_tag = Some(new_tag);
+    }
+}
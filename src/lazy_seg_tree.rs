@@ -27,11 +27,14 @@
 //! assert_eq!(tree.query(..), 45);
 //! ```
 
-use crate::{utils, SegTreeNode};
+use crate::lazy_nodes::{self, LazyNodes};
+use crate::{utils, FrozenLazySegTree, SegTree, SegTreeNode, SegTreeSpec};
+use alloc::{borrow::Cow, boxed::Box, vec, vec::Vec};
 use core::marker::PhantomData;
 use core::ops::RangeBounds;
 
 use core::cell::RefCell;
+#[cfg(feature = "std")]
 use core::fmt::Display;
 
 /// Specification for lazy segment tree operations.
@@ -74,6 +77,14 @@ pub trait LazySegTreeSpec {
 
     /// Applies update to data value, accounting for range size.
     fn op_update_on_data(u: &Self::U, d: &mut Self::T, size: usize);
+
+    /// Returns `true` if `u` is an identity update that changes nothing.
+    ///
+    /// Identity updates are skipped entirely, so no tags are written.
+    /// Defaults to `false`, which always stores the update.
+    fn is_identity_update(_u: &Self::U) -> bool {
+        false
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -90,9 +101,7 @@ impl<Spec: LazySegTreeSpec> LazySegTree<Spec> {
     // ===== CONSTRUCTORS =====
 
     fn size_to_max_size_and_depth(size: usize) -> (usize, u32) {
-        if size == 0 {
-            panic!("LazySegTree must have a positive size");
-        }
+        // An empty tree keeps a single padding leaf, matching `SegTree`
         let max_size = size.next_power_of_two();
         let max_depth = max_size.trailing_zeros();
         (max_size, max_depth)
@@ -102,9 +111,6 @@ impl<Spec: LazySegTreeSpec> LazySegTree<Spec> {
     ///
     /// # Time Complexity
     /// O(n)
-    ///
-    /// # Panics
-    /// Panics if `size` is 0.
     pub fn new(size: usize) -> Self {
         let (max_size, max_depth) = Self::size_to_max_size_and_depth(size);
         Self {
@@ -121,9 +127,6 @@ impl<Spec: LazySegTreeSpec> LazySegTree<Spec> {
     ///
     /// # Time Complexity
     /// O(n)
-    ///
-    /// # Panics
-    /// Panics if `values` is empty.
     pub fn from_slice(values: &[Spec::T]) -> Self {
         let size = values.len();
         let (max_size, max_depth) = Self::size_to_max_size_and_depth(size);
@@ -131,11 +134,7 @@ impl<Spec: LazySegTreeSpec> LazySegTree<Spec> {
 
         if size > 0 {
             data[max_size..(max_size + size)].clone_from_slice(values);
-            for i in (1..max_size).rev() {
-                let mut v = data[i * 2].clone();
-                Spec::op_on_data(&mut v, &data[i * 2 + 1]);
-                data[i] = v;
-            }
+            lazy_nodes::build::<Spec>(&mut data);
         }
 
         Self {
@@ -152,9 +151,6 @@ impl<Spec: LazySegTreeSpec> LazySegTree<Spec> {
     ///
     /// # Time Complexity
     /// O(n)
-    ///
-    /// # Panics
-    /// Panics if `values` is empty.
     pub fn from_vec(values: Vec<Spec::T>) -> Self {
         let size = values.len();
         let (max_size, max_depth) = Self::size_to_max_size_and_depth(size);
@@ -164,11 +160,7 @@ impl<Spec: LazySegTreeSpec> LazySegTree<Spec> {
             for (i, v) in values.into_iter().enumerate() {
                 data[max_size + i] = v;
             }
-            for i in (1..max_size).rev() {
-                let mut v = data[i * 2].clone();
-                Spec::op_on_data(&mut v, &data[i * 2 + 1]);
-                data[i] = v;
-            }
+            lazy_nodes::build::<Spec>(&mut data);
         }
 
         Self {
@@ -181,10 +173,136 @@ impl<Spec: LazySegTreeSpec> LazySegTree<Spec> {
         }
     }
 
+    /// Creates a new lazy segment tree seeded with the current values of a
+    /// plain [`SegTree`] over the same element type.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::{LazySegTreeAddSum, SegTreeSum};
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// let mut lazy = LazySegTreeAddSum::<i32>::from_seg_tree(&tree);
+    /// lazy.update(.., 1);
+    /// assert_eq!(lazy.query(..), 9);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_seg_tree<S>(tree: &SegTree<S>) -> Self
+    where
+        S: SegTreeSpec<T = Spec::T>,
+    {
+        Self::from_slice(tree.leaves())
+    }
+
+    /// Creates a new lazy segment tree from owned or borrowed values.
+    ///
+    /// Owned vectors are moved into the tree like [`from_vec`](Self::from_vec);
+    /// borrowed slices are cloned like [`from_slice`](Self::from_slice).
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let values = vec![1, 2, 3];
+    /// let borrowed = LazySegTreeAddSum::<i32>::build(&values[..]);
+    /// let owned = LazySegTreeAddSum::<i32>::build(values);
+    /// assert_eq!(borrowed.query(..), owned.query(..));
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn build<'a>(values: impl Into<Cow<'a, [Spec::T]>>) -> Self
+    where
+        Spec::T: 'a,
+    {
+        match values.into() {
+            Cow::Borrowed(slice) => Self::from_slice(slice),
+            Cow::Owned(vec) => Self::from_vec(vec),
+        }
+    }
+
     // ===== PUBLIC INTERFACE =====
 
+    /// Returns the number of elements in the tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let values = vec![1, 2, 3, 4, 5];
+    /// let tree = LazySegTreeAddSum::<i32>::from_slice(&values);
+    /// assert_eq!(tree.len(), values.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the tree holds no elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// assert!(LazySegTreeAddSum::<i32>::new(0).is_empty());
+    /// assert!(!LazySegTreeAddSum::<i32>::new(3).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns the number of leaves in the internal layout, i.e. the next
+    /// power of two of [`len`](Self::len).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// assert_eq!(LazySegTreeAddSum::<i32>::new(5).capacity(), 8);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.max_size
+    }
+
+    /// Returns the number of levels below the root, i.e. `log2(capacity())`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// assert_eq!(LazySegTreeAddSum::<i32>::new(5).height(), 3);
+    /// ```
+    pub fn height(&self) -> u32 {
+        self.max_depth
+    }
+
+    /// Returns an estimate of the bytes held by the node and tag storage,
+    /// `2 * capacity() * (size_of::<T>() + size_of::<Option<U>>())`.
+    ///
+    /// Heap memory owned by the values and tags themselves is not counted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// // 16 nodes of i64 plus 16 tags of Option<i64>
+    /// assert_eq!(LazySegTreeAddSum::<i64>::new(5).mem_bytes(), 16 * 8 + 16 * 16);
+    /// ```
+    pub fn mem_bytes(&self) -> usize {
+        core::mem::size_of_val(&**self.data.borrow())
+            + core::mem::size_of_val(&**self.tags.borrow())
+    }
+
     /// Queries the aggregated value over the given range.
     ///
+    /// A query over the whole tree reads the root like [`total`](Self::total)
+    /// instead of walking the boundary paths.
+    ///
     /// # Example
     /// ```
     /// use array_range_query::helpers::LazySegTreeAddMax;
@@ -204,40 +322,115 @@ impl<Spec: LazySegTreeSpec> LazySegTree<Spec> {
         if left_inp == right_inp {
             return Spec::ID;
         }
+        if left_inp == 0 && right_inp == self.size {
+            return self.total();
+        }
 
-        let mut l = self.max_size + left_inp;
-        let mut r = self.max_size + right_inp;
+        let mut data = self.data.borrow_mut();
+        let mut tags = self.tags.borrow_mut();
+        LazyNodes::<Spec>::new(&mut data, &mut tags, self.max_depth)
+            .query(self.max_size + left_inp, self.max_size + right_inp)
+    }
 
-        for i in (1..=self.max_depth).rev() {
-            // Checks if the node is not a left bound
-            if ((l >> i) << i) != l {
-                self.push_node(SegTreeNode(l >> i));
-            }
-            if ((r >> i) << i) != r {
-                self.push_node(SegTreeNode((r - 1) >> i));
-            }
+    /// Returns the aggregate of every element, the same as `query(..)`.
+    ///
+    /// Only the root's pending tag is applied, so nothing is pushed.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// tree.update(1..3, 10);
+    /// assert_eq!(tree.total(), 35);
+    /// assert_eq!(tree.total(), tree.query(..));
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(1)
+    pub fn total(&self) -> Spec::T {
+        self.eval(SegTreeNode(1))
+    }
+
+    /// Queries the aggregated value over the given range through `&mut self`.
+    ///
+    /// Returns the same result as [`query`](Self::query), but pushes tags via
+    /// direct mutable access instead of `RefCell` borrows, so it can never hit
+    /// a borrow panic. Prefer it whenever `&mut self` is available.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// tree.update(1..3, 10);
+    /// assert_eq!(tree.query_mut(..), 35);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query_mut<R: RangeBounds<usize>>(&mut self, range: R) -> Spec::T {
+        let (left_inp, right_inp) = utils::parse_range(range, self.size);
+        utils::validate_range(left_inp, right_inp, self.size);
+        if left_inp == right_inp {
+            return Spec::ID;
+        }
+        if left_inp == 0 && right_inp == self.size {
+            return self.nodes_mut().eval(SegTreeNode(1));
         }
 
-        let mut res = Spec::ID;
+        let (l, r) = (self.max_size + left_inp, self.max_size + right_inp);
+        self.nodes_mut().query(l, r)
+    }
 
-        while l < r {
-            if l & 1 != 0 {
-                Spec::op_on_data(&mut res, &self.eval(SegTreeNode(l)));
-                l += 1;
-            }
-            if r & 1 != 0 {
-                r -= 1;
-                Spec::op_on_data(&mut res, &self.eval(SegTreeNode(r)));
+    /// Returns the individual values of the elements in the given range,
+    /// with every pending update applied.
+    ///
+    /// Pushes the tags of every node above the range down to its leaves, so
+    /// later queries over the same range find no pending tags on the way.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// tree.update(1..4, 10);
+    /// assert_eq!(tree.collect_range(..), vec![1, 12, 13, 14, 5]);
+    /// assert_eq!(tree.collect_range(3..), vec![14, 5]);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(k + log n) for `k` returned elements
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn collect_range<R: RangeBounds<usize>>(&self, range: R) -> Vec<Spec::T> {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        if left == right {
+            return Vec::new();
+        }
+
+        let l = self.max_size + left;
+        let r = self.max_size + right - 1;
+        for i in (1..=self.max_depth).rev() {
+            for node in (l >> i)..=(r >> i) {
+                self.push_node(SegTreeNode(node));
             }
-            l >>= 1;
-            r >>= 1;
         }
 
-        res
+        (l..=r).map(|leaf| self.eval(SegTreeNode(leaf))).collect()
     }
 
     /// Applies an update to all elements in the given range.
     ///
+    /// `value` is moved into the last of the O(log n) nodes covering the
+    /// range and cloned only for the others, so a single-node update such as
+    /// [`update_point`](Self::update_point) never clones it.
+    ///
     /// # Example
     /// ```
     /// use array_range_query::helpers::LazySegTreeAddMax;
@@ -255,147 +448,264 @@ impl<Spec: LazySegTreeSpec> LazySegTree<Spec> {
     pub fn update<R: RangeBounds<usize>>(&mut self, range: R, value: Spec::U) {
         let (left_inp, right_inp) = utils::parse_range(range, self.size);
         utils::validate_range(left_inp, right_inp, self.size);
-        if left_inp == right_inp {
+        if left_inp == right_inp || Spec::is_identity_update(&value) {
             return;
         }
 
-        let mut l = self.max_size + left_inp;
-        let mut r = self.max_size + right_inp;
-
-        for i in (1..=self.max_depth).rev() {
-            if ((l >> i) << i) != l {
-                self.push_node_mut(SegTreeNode(l >> i));
-            }
-            if ((r >> i) << i) != r {
-                self.push_node_mut(SegTreeNode((r - 1) >> i));
-            }
-        }
+        let (l, r) = (self.max_size + left_inp, self.max_size + right_inp);
+        self.nodes_mut().update(l, r, value);
+    }
 
-        let l0 = l;
-        let r0 = r;
+    /// Applies an update to the single element at `index`, equivalent to
+    /// `update(index..index + 1, value)`.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// tree.update_point(1, 10);
+    /// assert_eq!(tree.query(..), 16);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn update_point(&mut self, index: usize, value: Spec::U) {
+        assert!(index < self.size, "update index out of bounds");
+        self.update(index..=index, value);
+    }
 
-        while l < r {
-            if l & 1 != 0 {
-                Self::combine_tag_option(&mut self.tags.get_mut()[l], &value);
-                l += 1;
-            }
-            if r & 1 != 0 {
-                r -= 1;
-                Self::combine_tag_option(&mut self.tags.get_mut()[r], &value);
-            }
-            l >>= 1;
-            r >>= 1;
+    /// Applies a batch of range updates in order, equivalent to calling
+    /// [`update`](Self::update) for each one.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::new(5);
+    /// tree.update_many([(0..2, 1), (1..5, 10), (4..5, 100)]);
+    /// assert_eq!(tree.query(..), 142);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(k log n) for `k` updates
+    ///
+    /// # Panics
+    /// Panics if any range is invalid or out of bounds; earlier updates in
+    /// the batch stay applied.
+    pub fn update_many<I, R>(&mut self, updates: I)
+    where
+        I: IntoIterator<Item = (R, Spec::U)>,
+        R: RangeBounds<usize>,
+    {
+        for (range, value) in updates {
+            self.update(range, value);
         }
+    }
 
-        for i in 1..=self.max_depth {
-            if ((l0 >> i) << i) != l0 {
-                self.pull_node(SegTreeNode(l0 >> i));
-            }
-            if ((r0 >> i) << i) != r0 {
-                self.pull_node(SegTreeNode((r0 - 1) >> i));
-            }
-        }
+    /// Resets every value to `Spec::ID` and drops all pending tags, keeping
+    /// the length and the allocated storage.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.update(..2, 5);
+    /// tree.clear();
+    /// assert_eq!(tree.query(..), 0);
+    /// assert_eq!(tree.pending_tag_count(), 0);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn clear(&mut self) {
+        self.data.get_mut().fill(Spec::ID);
+        self.tags.get_mut().fill(None);
     }
 
-    // ===== PRIVATE HELPER METHODS =====
+    /// Sets every value to `value`, dropping all pending tags and rebuilding
+    /// the internal nodes.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::new(5);
+    /// tree.fill(3);
+    /// assert_eq!(tree.query(..), 15);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn fill(&mut self, value: Spec::T) {
+        self.tags.get_mut().fill(None);
+        let max_size = self.max_size;
+        let data = self.data.get_mut();
+        data[max_size..max_size + self.size].fill(value);
+        lazy_nodes::build::<Spec>(data);
+    }
 
-    fn pull_node(&mut self, node: SegTreeNode) {
-        if node.is_leaf(self.max_depth) {
-            return;
+    /// Consumes the tree, applying every pending tag, and returns a read-only
+    /// [`FrozenLazySegTree`] that can be queried concurrently.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.update(..2, 5);
+    /// let frozen = tree.freeze();
+    /// assert_eq!(frozen.query(..), 20);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn freeze(mut self) -> FrozenLazySegTree<Spec> {
+        for i in 1..self.max_size * 2 {
+            self.nodes_mut().push(SegTreeNode(i));
         }
-        let mut res = self.eval_mut(node.left_child());
-        let right_val = self.eval_mut(node.right_child());
-        Spec::op_on_data(&mut res, &right_val);
-        self.data.get_mut()[node.0] = res;
+        FrozenLazySegTree::from_parts(self.size, self.max_size, self.data.into_inner())
     }
 
-    fn eval(&self, node: SegTreeNode) -> Spec::T {
-        let data = self.data.borrow();
+    /// Returns the pending tags on the path from the root down to the leaf at
+    /// `index`, root first, without consuming them.
+    ///
+    /// Useful for debugging why an element has its current value: deeper
+    /// tags are older, so the element's value is its stored leaf value with
+    /// these tags applied from the leaf upwards.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::{LazySegTreeAddSum, SegTreeNode};
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.update(2..4, 1);
+    /// tree.update(.., 5);
+    /// assert_eq!(tree.explain(2), vec![(SegTreeNode(1), 5), (SegTreeNode(3), 1)]);
+    /// assert_eq!(tree.query(2..3), 9);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn explain(&self, index: usize) -> Vec<(SegTreeNode, Spec::U)> {
+        assert!(index < self.size, "explain index out of bounds");
         let tags = self.tags.borrow();
-        let mut d = data[node.0].clone();
-        if let Some(tag) = &tags[node.0] {
-            Spec::op_update_on_data(tag, &mut d, node.size(self.max_depth));
-        }
-        d
+        let leaf = SegTreeNode(self.max_size + index);
+        let mut path: Vec<_> = core::iter::once(leaf)
+            .chain(leaf.ancestors())
+            .filter_map(|node| tags[node.0].clone().map(|tag| (node, tag)))
+            .collect();
+        path.reverse();
+        path
     }
 
-    fn eval_mut(&mut self, node: SegTreeNode) -> Spec::T {
-        let tag = self.tags.get_mut()[node.0].clone();
-        let mut d = self.data.get_mut()[node.0].clone();
-        if let Some(tag) = &tag {
-            Spec::op_update_on_data(tag, &mut d, node.size(self.max_depth));
-        }
-        d
+    /// Returns the number of nodes currently holding a pending lazy tag.
+    ///
+    /// # Example
+    /// ```
+    /// use array_range_query::LazySegTreeAddSum;
+    ///
+    /// let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3, 4]);
+    /// assert_eq!(tree.pending_tag_count(), 0);
+    /// tree.update(..2, 5);
+    /// assert_eq!(tree.pending_tag_count(), 1);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn pending_tag_count(&self) -> usize {
+        self.tags
+            .borrow()
+            .iter()
+            .filter(|tag| tag.is_some())
+            .count()
     }
 
-    /// Pushes the tag of the current node to its children after consuming it.
-    #[inline]
-    fn push_node(&self, node: SegTreeNode) {
-        let mut tags = self.tags.borrow_mut();
-        if let Some(tag) = tags[node.0].take() {
-            let mut data = self.data.borrow_mut();
-            Spec::op_update_on_data(&tag, &mut data[node.0], node.size(self.max_depth));
-            if !node.is_leaf(self.max_depth) {
-                Self::combine_tag_option(&mut tags[node.left_child().0], &tag);
-                Self::combine_tag_option(&mut tags[node.right_child().0], &tag);
-            }
+    // ===== PRIVATE HELPER METHODS =====
+
+    /// Pushes every pending tag down to the leaves.
+    fn flush(&self) {
+        for i in 1..self.max_size * 2 {
+            self.push_node(SegTreeNode(i));
         }
     }
 
-    #[inline]
-    fn push_node_mut(&mut self, node: SegTreeNode) {
-        if let Some(tag) = self.tags.get_mut()[node.0].take() {
-            let node_size = node.size(self.max_depth);
-            Spec::op_update_on_data(&tag, &mut self.data.get_mut()[node.0], node_size);
-            if !node.is_leaf(self.max_depth) {
-                let left_child_idx = node.left_child().0;
-                let right_child_idx = node.right_child().0;
-                let tags = self.tags.get_mut();
-                Self::combine_tag_option(&mut tags[left_child_idx], &tag);
-                Self::combine_tag_option(&mut tags[right_child_idx], &tag);
-            }
-        }
+    /// Borrows the node arrays as a [`LazyNodes`] view.
+    fn nodes_mut(&mut self) -> LazyNodes<'_, Spec> {
+        LazyNodes::new(self.data.get_mut(), self.tags.get_mut(), self.max_depth)
+    }
+
+    fn eval(&self, node: SegTreeNode) -> Spec::T {
+        lazy_nodes::eval::<Spec>(
+            &self.data.borrow(),
+            &self.tags.borrow(),
+            self.max_depth,
+            node,
+        )
     }
 
+    /// Pushes the tag of the current node to its children after consuming it.
     #[inline]
-    fn combine_tag_option(existing_tag: &mut Option<Spec::U>, new_tag: &Spec::U) {
-        if let Some(existing) = existing_tag {
-            Spec::op_on_update(existing, new_tag);
-        } else {
-            *existing_tag = Some(new_tag.clone());
-        }
+    fn push_node(&self, node: SegTreeNode) {
+        LazyNodes::<Spec>::new(
+            &mut self.data.borrow_mut(),
+            &mut self.tags.borrow_mut(),
+            self.max_depth,
+        )
+        .push(node);
     }
 }
 
-// ===== DISPLAY IMPLEMENTATION =====
+// ===== TRAIT IMPLEMENTATIONS =====
 
-fn print_tree_option<T: Display>(
-    f: &mut std::fmt::Formatter<'_>,
-    tree: &[&Option<T>],
-    index: usize,
-    depth: usize,
-    l: usize,
-    r: usize,
-) -> std::fmt::Result {
-    if index >= tree.len() {
-        return Ok(());
+/// Collects the initial values into a tree, delegating to
+/// [`from_vec`](LazySegTree::from_vec).
+///
+/// # Example
+///
+/// ```
+/// use array_range_query::LazySegTreeAddSum;
+///
+/// let t: LazySegTreeAddSum<i64> = (1..=5).collect();
+/// assert_eq!(t.query(..), 15);
+/// ```
+impl<Spec: LazySegTreeSpec> FromIterator<Spec::T> for LazySegTree<Spec> {
+    fn from_iter<I: IntoIterator<Item = Spec::T>>(iter: I) -> Self {
+        Self::from_vec(iter.into_iter().collect())
     }
+}
 
-    if let Some(value) = &tree[index] {
-        for _ in 0..depth {
-            write!(f, "  ")?;
+/// Two trees are equal when they hold the same logical elements.
+///
+/// Pending tags are flushed to the leaves first, so trees that reached the same
+/// values through different updates compare equal.
+impl<Spec: LazySegTreeSpec> PartialEq for LazySegTree<Spec>
+where
+    Spec::T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.size != other.size {
+            return false;
         }
-        writeln!(f, "{} (Index: {}, Covers [{}, {}))", value, index, l, r)?;
-    }
-
-    if index * 2 + 1 < tree.len() {
-        print_tree_option(f, tree, index * 2, depth + 1, l, (l + r) / 2)?;
-        print_tree_option(f, tree, index * 2 + 1, depth + 1, (l + r) / 2, r)?;
+        self.flush();
+        other.flush();
+        let data = self.data.borrow();
+        let other_data = other.data.borrow();
+        data[self.max_size..self.max_size + self.size]
+            == other_data[other.max_size..other.max_size + other.size]
     }
-
-    Ok(())
 }
 
+// ===== DISPLAY IMPLEMENTATION =====
+
+#[cfg(feature = "std")]
 impl<Spec: LazySegTreeSpec> Display for LazySegTree<Spec>
 where
     Spec::T: Display + PartialEq,
@@ -424,10 +734,10 @@ where
         let tag_values_slice = tags.iter().collect::<Vec<_>>();
 
         writeln!(f, "  Data:")?;
-        print_tree_option(f, &data_values_slice, 1, 2, 0, self.max_size)?;
+        utils::print_tree_option(f, &data_values_slice, 1, 2, 0, self.max_size)?;
 
         writeln!(f, "  Lazy Tags:")?;
-        print_tree_option(f, &tag_values_slice, 1, 2, 0, self.max_size)?;
+        utils::print_tree_option(f, &tag_values_slice, 1, 2, 0, self.max_size)?;
 
         writeln!(f, "}}")?;
 
@@ -463,6 +773,168 @@ mod tests {
         }
     }
 
+    static COUNTED_DATA_OPS: core::sync::atomic::AtomicUsize =
+        core::sync::atomic::AtomicUsize::new(0);
+
+    /// `RangeAddSum` that counts how often `op_on_data` is called.
+    struct CountingRangeAddSum;
+
+    impl LazySegTreeSpec for CountingRangeAddSum {
+        type T = i64;
+        type U = i64;
+        const ID: Self::T = 0;
+
+        fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+            COUNTED_DATA_OPS.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+            *d1 += *d2;
+        }
+
+        fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+            *u1 += *u2;
+        }
+
+        fn op_update_on_data(u: &Self::U, d: &mut Self::T, size: usize) {
+            *d += u * size as i64;
+        }
+    }
+
+    static CLONED_UPDATES: core::sync::atomic::AtomicUsize =
+        core::sync::atomic::AtomicUsize::new(0);
+
+    /// Add update that counts how often it is cloned.
+    #[derive(Debug)]
+    struct CountedAdd(i64);
+
+    impl Clone for CountedAdd {
+        fn clone(&self) -> Self {
+            CLONED_UPDATES.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+            CountedAdd(self.0)
+        }
+    }
+
+    /// `RangeAddSum` whose update type counts its clones.
+    struct CountedCloneAddSum;
+
+    impl LazySegTreeSpec for CountedCloneAddSum {
+        type T = i64;
+        type U = CountedAdd;
+        const ID: Self::T = 0;
+
+        fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+            *d1 += *d2;
+        }
+
+        fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+            u1.0 += u2.0;
+        }
+
+        fn op_update_on_data(u: &Self::U, d: &mut Self::T, size: usize) {
+            *d += u.0 * size as i64;
+        }
+    }
+
+    #[test]
+    fn update_moves_value_into_last_node() {
+        use core::sync::atomic::Ordering;
+
+        let values: Vec<i64> = (0..13).collect();
+        let mut expected = values.clone();
+        let mut tree = LazySegTree::<CountedCloneAddSum>::from_vec(values);
+        let seg_tree = SegTree::<crate::helpers::SegTreeSumSpec<i64>>::new(13);
+
+        for (l, r) in [(0, 13), (3, 4), (1, 12), (5, 9), (0, 1), (12, 13)] {
+            // Fresh tree each time, so no pushed-down tags are cloned
+            let mut fresh = LazySegTree::<CountedCloneAddSum>::new(13);
+            CLONED_UPDATES.store(0, Ordering::Relaxed);
+            fresh.update(l..r, CountedAdd(1));
+            let nodes = seg_tree.canonical_nodes(l..r).len();
+            assert_eq!(
+                CLONED_UPDATES.load(Ordering::Relaxed),
+                nodes - 1,
+                "[{}, {})",
+                l,
+                r
+            );
+
+            tree.update(l..r, CountedAdd(l as i64 + 1));
+            for v in &mut expected[l..r] {
+                *v += l as i64 + 1;
+            }
+        }
+        for l in 0..=expected.len() {
+            for r in l..=expected.len() {
+                assert_eq!(tree.query(l..r), expected[l..r].iter().sum::<i64>());
+            }
+        }
+    }
+
+    #[test]
+    fn full_range_query_reads_root() {
+        use core::sync::atomic::Ordering;
+
+        let mut tree = LazySegTree::<CountingRangeAddSum>::from_vec((1..=6).collect());
+        tree.update(0..4, 1);
+        tree.update(1..6, 2);
+        let pending = tree.pending_tag_count();
+        assert!(pending > 0);
+
+        COUNTED_DATA_OPS.store(0, Ordering::Relaxed);
+        assert_eq!(tree.query(..), 21 + 4 + 10);
+        assert_eq!(tree.query(0..6), 21 + 4 + 10);
+        assert_eq!(tree.query_mut(..), 21 + 4 + 10);
+        assert_eq!(COUNTED_DATA_OPS.load(Ordering::Relaxed), 0);
+        assert_eq!(tree.pending_tag_count(), pending);
+
+        // Power-of-two sizes can hold a tag on the root itself
+        let mut tree = LazySegTree::<CountingRangeAddSum>::from_vec((1..=8).collect());
+        tree.update(.., 3);
+        assert_eq!(tree.query(..), 36 + 24);
+        assert_eq!(tree.query_mut(..), 36 + 24);
+    }
+
+    #[test]
+    fn collect_range_matches_brute_force() {
+        let mut model: Vec<i64> = (0..11).map(|i| i * i - 20).collect();
+        let mut tree = LazySegTree::<RangeAddSum>::from_slice(&model);
+
+        for (l, r, add) in [(0, 11, 3), (2, 7, -5), (6, 9, 10), (4, 5, 1), (0, 3, 7)] {
+            tree.update(l..r, add);
+            model[l..r].iter_mut().for_each(|x| *x += add);
+            for a in 0..=model.len() {
+                for b in a..=model.len() {
+                    assert_eq!(tree.collect_range(a..b), model[a..b]);
+                    assert_eq!(tree.query(a..b), model[a..b].iter().sum::<i64>());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn update_many_matches_sequential_updates() {
+        let values: Vec<i64> = (0..13).map(|i| i * 3 - 7).collect();
+        let updates: Vec<(core::ops::Range<usize>, i64)> = (0..40)
+            .map(|k| {
+                let a = (k * 7) % 14;
+                let b = (k * 11 + 3) % 14;
+                (a.min(b)..a.max(b), k as i64 - 20)
+            })
+            .collect();
+
+        let mut batched = LazySegTree::<RangeAddSum>::from_slice(&values);
+        batched.update_many(updates.iter().cloned());
+        let mut sequential = LazySegTree::<RangeAddSum>::from_slice(&values);
+        for (range, value) in updates {
+            sequential.update(range, value);
+        }
+
+        assert_eq!(batched, sequential);
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                assert_eq!(batched.query(l..r), sequential.query(l..r));
+            }
+        }
+    }
+
     #[test]
     fn constructors() {
         // `new` should create an identity-filled tree
@@ -479,6 +951,118 @@ mod tests {
         let tree_vec = LazySegTree::<RangeAddSum>::from_vec(vec![1i64, 2, 3]);
         assert_eq!(tree_vec.query(..), 6);
         assert_eq!(tree_vec.query(1..2), 2);
+
+        // `collect` should match `from_vec`
+        let tree_iter: LazySegTree<RangeAddSum> = arr.iter().copied().collect();
+        assert_eq!(tree_iter, tree_slice);
+        assert_eq!(tree_iter.capacity(), 8);
+        let empty: LazySegTree<RangeAddSum> = core::iter::empty().collect();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn height_and_mem_bytes() {
+        use core::mem::size_of;
+
+        for size in [0, 1, 5, 8, 1000] {
+            let tree = LazySegTree::<RangeAddSum>::new(size);
+            let max_size = size.next_power_of_two();
+            assert_eq!(tree.height(), max_size.trailing_zeros());
+            assert_eq!(
+                tree.mem_bytes(),
+                2 * max_size * (size_of::<i64>() + size_of::<Option<i64>>())
+            );
+        }
+    }
+
+    #[test]
+    fn empty_tree() {
+        let mut trees = [
+            LazySegTree::<RangeAddSum>::new(0),
+            LazySegTree::<RangeAddSum>::from_slice(&[]),
+            LazySegTree::<RangeAddSum>::from_vec(Vec::new()),
+        ];
+        for tree in &mut trees {
+            assert_eq!(tree.query(..), 0);
+            assert_eq!(tree.query(0..0), 0);
+            assert_eq!(tree.query_mut(..), 0);
+            tree.update(.., 5);
+            assert_eq!(tree.query(..), 0);
+            assert_eq!(tree.total(), 0);
+            assert_eq!(tree.pending_tag_count(), 0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn empty_tree_rejects_non_empty_range() {
+        let tree = LazySegTree::<RangeAddSum>::new(0);
+        tree.query(0..1);
+    }
+
+    #[test]
+    fn clear_keeps_storage() {
+        let mut tree = LazySegTree::<RangeAddSum>::from_vec((1..=10).collect());
+        tree.update(2..7, 3);
+        let data = tree.data.get_mut().as_ptr();
+        let tags = tree.tags.get_mut().as_ptr();
+
+        tree.clear();
+        assert_eq!(tree.data.get_mut().as_ptr(), data);
+        assert_eq!(tree.tags.get_mut().as_ptr(), tags);
+        assert_eq!(tree.len(), 10);
+        assert_eq!(tree.query(..), 0);
+        assert_eq!(tree.pending_tag_count(), 0);
+
+        tree.update(.., 1);
+        assert_eq!(tree.query(3..5), 2);
+    }
+
+    #[test]
+    fn fill_overrides_pending_tags() {
+        let mut tree = LazySegTree::<RangeAddSum>::from_vec(vec![9, 8, 7, 6, 5]);
+        tree.update(1..4, 10);
+
+        tree.fill(3);
+        assert_eq!(tree.pending_tag_count(), 0);
+        assert_eq!(tree.query(..), 15);
+        assert_eq!(tree.query(2..4), 6);
+
+        tree.update(..2, 1);
+        assert_eq!(tree.query(..), 17);
+    }
+
+    #[test]
+    fn from_seg_tree_matches_source() {
+        use crate::helpers::{LazySegTreeAddSum, SegTreeSum};
+
+        let mut tree = SegTreeSum::<i64>::from_vec((1..=10).collect());
+        tree.update(4, 100);
+        let mut lazy = LazySegTreeAddSum::<i64>::from_seg_tree(&tree);
+
+        assert_eq!(lazy.len(), tree.len());
+        for l in 0..=10 {
+            for r in l..=10 {
+                assert_eq!(lazy.query(l..r), tree.query(l..r));
+            }
+        }
+
+        lazy.update(2..6, 1);
+        assert_eq!(lazy.query(..), tree.query(..) + 4);
+    }
+
+    #[test]
+    fn build_owned_borrowed_and_cow() {
+        let values = vec![1i64, 2, 3, 4, 5];
+
+        let from_borrowed = LazySegTree::<RangeAddSum>::build(&values[..]);
+        let from_cow = LazySegTree::<RangeAddSum>::build(Cow::Borrowed(&values[..]));
+        let from_owned = LazySegTree::<RangeAddSum>::build(values);
+
+        for tree in [&from_borrowed, &from_cow, &from_owned] {
+            assert_eq!(tree.query(..), 15);
+            assert_eq!(tree.query(1..4), 9);
+        }
     }
 
     #[test]
@@ -550,6 +1134,55 @@ mod tests {
         assert_eq!(tree.query(7..10), expected[7] + expected[8] + expected[9]);
     }
 
+    #[test]
+    fn update_point_matches_unit_range_update() {
+        let values: Vec<i64> = (1..=10).collect();
+        let mut by_point = LazySegTree::<RangeAddSum>::from_slice(&values);
+        let mut by_range = LazySegTree::<RangeAddSum>::from_slice(&values);
+
+        by_range.update(3..8, 4);
+        by_point.update(3..8, 4);
+        for (i, x) in [(2, 7), (5, -3), (9, 100), (2, 1)] {
+            by_point.update_point(i, x);
+            by_range.update(i..i + 1, x);
+            for l in 0..=values.len() {
+                for r in l..=values.len() {
+                    assert_eq!(by_point.query(l..r), by_range.query(l..r));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "update index out of bounds")]
+    fn update_point_out_of_bounds() {
+        let mut tree = LazySegTree::<RangeAddSum>::new(4);
+        tree.update_point(4, 1);
+    }
+
+    #[test]
+    fn explain_tags_compose_to_contribution() {
+        let values: Vec<i64> = (0..13).map(|i| i * 10).collect();
+        let mut tree = LazySegTree::<RangeAddSum>::from_slice(&values);
+        tree.update(2..11, 3);
+        tree.update(..7, -1);
+        tree.update(5..6, 100);
+        let pending = tree.pending_tag_count();
+        let paths: Vec<_> = (0..values.len()).map(|i| tree.explain(i)).collect();
+        // Explaining does not consume tags
+        assert_eq!(tree.pending_tag_count(), pending);
+
+        for (i, path) in paths.iter().enumerate() {
+            assert!(path.windows(2).all(|w| w[0].0.depth() < w[1].0.depth()));
+            // Leaf data is untouched by `update`, so the tags carry every change
+            let contribution: i64 = path.iter().map(|(_, tag)| tag).sum();
+            let expected =
+                3 * (2..11).contains(&i) as i64 - (..7).contains(&i) as i64 + 100 * (i == 5) as i64;
+            assert_eq!(contribution, expected);
+            assert_eq!(tree.query(i..=i), values[i] + expected);
+        }
+    }
+
     #[test]
     fn test_overlapping_updates() {
         let mut tree = LazySegTree::<RangeAddSum>::new(10);
@@ -561,6 +1194,120 @@ mod tests {
         assert_eq!(tree.query(4..6), 30);
     }
 
+    /// Range assign with `(first, last)` queries; `op_on_data` is not
+    /// commutative, so combining nodes out of order gives wrong endpoints.
+    struct AssignFirstLast;
+
+    impl LazySegTreeSpec for AssignFirstLast {
+        type T = (Option<i64>, Option<i64>);
+        type U = i64;
+        const ID: Self::T = (None, None);
+
+        fn op_on_data(d1: &mut Self::T, d2: &Self::T) {
+            d1.0 = d1.0.or(d2.0);
+            d1.1 = d2.1.or(d1.1);
+        }
+
+        fn op_on_update(u1: &mut Self::U, u2: &Self::U) {
+            *u1 = *u2;
+        }
+
+        fn op_update_on_data(u: &Self::U, d: &mut Self::T, _size: usize) {
+            *d = (Some(*u), Some(*u));
+        }
+    }
+
+    #[test]
+    fn non_commutative_query_matches_brute_force() {
+        let mut values: Vec<i64> = (0..23).map(|i| i * 10).collect();
+        let mut tree = LazySegTree::<AssignFirstLast>::from_vec(
+            values.iter().map(|&v| (Some(v), Some(v))).collect(),
+        );
+        let n = values.len();
+
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        for round in 0..200 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let a = ((state >> 33) % (n as u64 + 1)) as usize;
+            let b = ((state >> 17) % (n as u64 + 1)) as usize;
+            let (l, r) = (a.min(b), a.max(b));
+            if round % 3 == 0 {
+                tree.update(l..r, round);
+                values[l..r].fill(round);
+            }
+
+            for l in 0..=n {
+                for r in l..=n {
+                    let expected = (values[l..r].first().copied(), values[l..r].last().copied());
+                    assert_eq!(tree.query(l..r), expected, "query [{}, {})", l, r);
+                    assert_eq!(tree.query_mut(l..r), expected, "query_mut [{}, {})", l, r);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn query_mut_matches_query() {
+        let mut tree = LazySegTree::<RangeAddSum>::from_vec((1..=10).collect());
+        tree.update(2..7, 3);
+        tree.update(..4, -1);
+
+        for l in 0..=10 {
+            for r in l..=10 {
+                let expected = tree.query(l..r);
+                assert_eq!(tree.query_mut(l..r), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn frozen_shared_across_threads_with_arc() {
+        use std::sync::Arc;
+
+        let mut tree = LazySegTree::<RangeAddSum>::from_vec((0..100).collect());
+        tree.update(10..60, 3);
+        tree.update(40..90, -1);
+        let expected: Vec<i64> = (0..100)
+            .map(|i| i + 3 * (10..60).contains(&i) as i64 - (40..90).contains(&i) as i64)
+            .collect();
+
+        let frozen = Arc::new(tree.freeze());
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let frozen = Arc::clone(&frozen);
+                let expected = expected.clone();
+                std::thread::spawn(move || {
+                    for l in (t..100).step_by(7) {
+                        for r in l..=100 {
+                            assert_eq!(frozen.query(l..r), expected[l..r].iter().sum::<i64>());
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(frozen.len(), 100);
+    }
+
+    #[test]
+    fn eq_after_flushing_tags() {
+        let mut lazy = LazySegTree::<RangeAddSum>::from_vec(vec![1, 2, 3, 4, 5]);
+        lazy.update(1..4, 10);
+        lazy.update(..2, -1);
+
+        let eager = LazySegTree::<RangeAddSum>::from_vec(vec![0, 11, 13, 14, 5]);
+        assert!(lazy == eager);
+        assert_eq!(lazy.pending_tag_count(), 0);
+
+        lazy.update(4..5, 1);
+        assert!(lazy != eager);
+        assert!(lazy != LazySegTree::<RangeAddSum>::from_vec(vec![0, 11, 13, 14]));
+    }
+
     #[test]
     #[should_panic(expected = "Invalid range: got")]
     #[allow(clippy::reversed_empty_ranges)]
@@ -568,4 +1315,11 @@ mod tests {
         let tree = LazySegTree::<RangeAddSum>::new(10);
         tree.query(5..4);
     }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_update_inclusive_end_at_usize_max() {
+        let mut tree = LazySegTree::<RangeAddSum>::new(4);
+        tree.update(..=usize::MAX, 1);
+    }
 }
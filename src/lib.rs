@@ -17,19 +17,73 @@
 //! assert_eq!(lazy_tree.query(..), 45);
 //! ```
 //!
+//! # Features
+//!
+//! - `std` (enabled by default): enables the `Display` implementations.
+//!   Without it the crate is `#![no_std]` and only depends on `alloc`.
+//!
 //! For detailed documentation, examples, and use cases, see the [README](https://github.com/Sumanth-NR/array_range_query#readme).
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 pub(crate) mod utils;
 
+mod macros;
+
 mod seg_tree_node;
 pub use seg_tree_node::SegTreeNode;
 
 mod seg_tree;
-pub use seg_tree::{SegTree, SegTreeSpec};
+pub use seg_tree::{Checkpoint, SegTree, SegTreeSpec, Snapshot};
 
+mod lazy_nodes;
 mod lazy_seg_tree;
 pub use lazy_seg_tree::{LazySegTree, LazySegTreeSpec};
 
+mod frozen_lazy_seg_tree;
+pub use frozen_lazy_seg_tree::FrozenLazySegTree;
+
+mod copy_lazy_seg_tree;
+pub use copy_lazy_seg_tree::CopyLazySegTree;
+
+mod dyn_lazy_seg_tree;
+pub use dyn_lazy_seg_tree::DynLazySegTree;
+
+mod nary_seg_tree;
+pub use nary_seg_tree::NarySegTree;
+
+mod merge_sort_tree;
+pub use merge_sort_tree::MergeSortTree;
+
+mod count_tree;
+pub use count_tree::CountTree;
+
+mod distinct_count_tree;
+pub use distinct_count_tree::DistinctCountTree;
+
+mod range_value_count_tree;
+pub use range_value_count_tree::RangeValueCountTree;
+
+mod mo_order;
+pub use mo_order::mo_order;
+
+mod fenwick_tree;
+pub use fenwick_tree::FenwickTree;
+
+mod sparse_seg_tree;
+pub use sparse_seg_tree::SparseSegTree;
+
+mod seg_tree_beats;
+pub use seg_tree_beats::{SegTreeBeats, SegTreeBeatsChmaxSum};
+
 pub mod helpers;
-pub use helpers::{LazySegTreeAddMax, LazySegTreeAddMin, LazySegTreeAddSum, LazySegTreeReplaceSum};
-pub use helpers::{SegTreeMax, SegTreeMin, SegTreeSum};
+pub use helpers::{
+    LazySegTreeAddMax, LazySegTreeAddMin, LazySegTreeAddSum, LazySegTreeAssignAddSum,
+    LazySegTreeCheckedAddSum, LazySegTreeMulSum, LazySegTreeOptReplaceSum, LazySegTreeReplaceSum,
+    LazySegTreeSaturatingAddMax, LazySegTreeSaturatingAddMin,
+};
+pub use helpers::{
+    SegTreeHash, SegTreeMatrix, SegTreeMax, SegTreeMaxSubarray, SegTreeMin, SegTreeSum,
+};
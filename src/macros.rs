@@ -0,0 +1,146 @@
+//! Declarative macros for composing specifications.
+
+/// Builds a [`SegTreeSpec`](crate::SegTreeSpec) over a tuple from two or
+/// three component specs.
+///
+/// The generated spec stores `(A::T, B::T)` (or `(A::T, B::T, C::T)`), uses
+/// the tuple of component identities as `id()`, and applies each component's
+/// `op` to its own tuple field. Only `id()` is generated, so components that
+/// define just `id()` work too.
+///
+/// # Examples
+///
+/// ```
+/// use array_range_query::helpers::{SegTreeMaxSpec, SegTreeMinSpec, SegTreeSumSpec};
+/// use array_range_query::{seg_tree_tuple_spec, SegTree};
+///
+/// seg_tree_tuple_spec!(
+///     /// Tracks (sum, min, max) of a range.
+///     pub SumMinMax = (SegTreeSumSpec<i64>, SegTreeMinSpec<i64>, SegTreeMaxSpec<i64>)
+/// );
+///
+/// let tree = SegTree::<SumMinMax>::from_vec(vec![(3, 3, 3), (1, 1, 1), (4, 4, 4)]);
+/// assert_eq!(tree.query(..), (8, 1, 4));
+/// ```
+#[macro_export]
+macro_rules! seg_tree_tuple_spec {
+    ($(#[$meta:meta])* $vis:vis $name:ident = ($a:ty, $b:ty $(,)?)) => {
+        $(#[$meta])*
+        $vis struct $name;
+
+        impl $crate::SegTreeSpec for $name {
+            type T = (
+                <$a as $crate::SegTreeSpec>::T,
+                <$b as $crate::SegTreeSpec>::T,
+            );
+            fn id() -> Self::T {
+                (
+                    <$a as $crate::SegTreeSpec>::id(),
+                    <$b as $crate::SegTreeSpec>::id(),
+                )
+            }
+
+            fn op(a: &mut Self::T, b: &Self::T) {
+                <$a as $crate::SegTreeSpec>::op(&mut a.0, &b.0);
+                <$b as $crate::SegTreeSpec>::op(&mut a.1, &b.1);
+            }
+        }
+    };
+    ($(#[$meta:meta])* $vis:vis $name:ident = ($a:ty, $b:ty, $c:ty $(,)?)) => {
+        $(#[$meta])*
+        $vis struct $name;
+
+        impl $crate::SegTreeSpec for $name {
+            type T = (
+                <$a as $crate::SegTreeSpec>::T,
+                <$b as $crate::SegTreeSpec>::T,
+                <$c as $crate::SegTreeSpec>::T,
+            );
+            fn id() -> Self::T {
+                (
+                    <$a as $crate::SegTreeSpec>::id(),
+                    <$b as $crate::SegTreeSpec>::id(),
+                    <$c as $crate::SegTreeSpec>::id(),
+                )
+            }
+
+            fn op(a: &mut Self::T, b: &Self::T) {
+                <$a as $crate::SegTreeSpec>::op(&mut a.0, &b.0);
+                <$b as $crate::SegTreeSpec>::op(&mut a.1, &b.1);
+                <$c as $crate::SegTreeSpec>::op(&mut a.2, &b.2);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{SegTreeMaxSpec, SegTreeSumSpec};
+    use crate::{SegTree, SegTreeSpec};
+
+    seg_tree_tuple_spec!(SumMaxSpec = (SegTreeSumSpec<i64>, SegTreeMaxSpec<i64>));
+
+    /// Hand-written equivalent of `SumMaxSpec`.
+    struct HandSumMaxSpec;
+    impl SegTreeSpec for HandSumMaxSpec {
+        type T = (i64, i64);
+        const ID: Self::T = (0, i64::MIN);
+
+        fn op(a: &mut Self::T, b: &Self::T) {
+            a.0 += b.0;
+            a.1 = a.1.max(b.1);
+        }
+    }
+
+    /// Concatenation monoid whose identity is only available through `id()`.
+    struct ConcatSpec;
+    impl SegTreeSpec for ConcatSpec {
+        type T = String;
+
+        fn id() -> Self::T {
+            String::new()
+        }
+
+        fn op(a: &mut Self::T, b: &Self::T) {
+            a.push_str(b);
+        }
+    }
+
+    seg_tree_tuple_spec!(ConcatSumSpec = (ConcatSpec, SegTreeSumSpec<i64>));
+
+    #[test]
+    fn test_tuple_spec_with_id_only_component() {
+        let values: Vec<(String, i64)> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .zip(1..)
+            .map(|(s, n)| (s.to_string(), n))
+            .collect();
+        let mut tree = SegTree::<ConcatSumSpec>::from_slice(&values);
+
+        assert_eq!(ConcatSumSpec::id(), (String::new(), 0));
+        assert_eq!(tree.query(1..4), ("bcd".to_string(), 9));
+        assert_eq!(tree.query(2..2), (String::new(), 0));
+
+        tree.update(0, ("z".to_string(), 10));
+        assert_eq!(tree.query(..), ("zbcde".to_string(), 24));
+    }
+
+    #[test]
+    fn test_tuple_spec_matches_hand_written() {
+        let values: Vec<(i64, i64)> = [5, -2, 9, 4, 4, -7, 3].iter().map(|&x| (x, x)).collect();
+        let mut generated = SegTree::<SumMaxSpec>::from_slice(&values);
+        let mut hand = SegTree::<HandSumMaxSpec>::from_slice(&values);
+
+        assert_eq!(SumMaxSpec::id(), HandSumMaxSpec::ID);
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                assert_eq!(generated.query(l..r), hand.query(l..r));
+            }
+        }
+
+        generated.update(2, (-10, -10));
+        hand.update(2, (-10, -10));
+        assert_eq!(generated.query(..), hand.query(..));
+        assert_eq!(generated.query(..), (-3, 5));
+    }
+}
@@ -0,0 +1,210 @@
+//! Merge-sort tree for order-statistic queries over index ranges.
+//!
+//! Each node of the power-of-two layout stores its subrange in sorted order.
+//! A range is split into the same O(log n) canonical nodes as
+//! [`SegTree::query`](crate::SegTree::query), and each node is binary searched,
+//! so counting queries run in O(log² n). The tree is static and uses
+//! O(n log n) space.
+//!
+//! # Example
+//!
+//! ```rust
+//! use array_range_query::MergeSortTree;
+//!
+//! let tree = MergeSortTree::from_vec(vec![5, 1, 4, 2, 3]);
+//! assert_eq!(tree.count_leq(1..4, &3), 2); // 1 and 2
+//! assert_eq!(tree.kth_in_range(.., 0), Some(1));
+//! assert_eq!(tree.kth_in_range(1..4, 2), Some(4));
+//! ```
+
+use crate::utils;
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::ops::RangeBounds;
+
+/// A static merge-sort tree answering "count ≤ x" and "k-th smallest"
+/// queries on index ranges.
+///
+/// # Examples
+///
+/// ```
+/// use array_range_query::MergeSortTree;
+///
+/// let tree = MergeSortTree::from_slice(&[3, 1, 4, 1, 5, 9, 2, 6]);
+/// assert_eq!(tree.count_leq(.., &3), 4); // 3, 1, 1, 2
+/// assert_eq!(tree.kth_in_range(2..6, 1), Some(4));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MergeSortTree<T: Ord + Clone> {
+    size: usize,
+    max_size: usize,
+    nodes: Box<[Vec<T>]>,
+}
+
+impl<T: Ord + Clone> MergeSortTree<T> {
+    // ===== CONSTRUCTORS =====
+
+    /// Creates a new merge-sort tree from a slice of values.
+    ///
+    /// # Time Complexity
+    /// O(n log n)
+    pub fn from_slice(values: &[T]) -> Self {
+        Self::from_vec(values.to_vec())
+    }
+
+    /// Creates a new merge-sort tree from a vector of values.
+    ///
+    /// # Time Complexity
+    /// O(n log n)
+    pub fn from_vec(values: Vec<T>) -> Self {
+        let size = values.len();
+        let max_size = size.next_power_of_two();
+        let mut nodes = vec![Vec::new(); 2 * max_size];
+
+        for (i, v) in values.into_iter().enumerate() {
+            nodes[max_size + i] = vec![v];
+        }
+        for i in (1..max_size).rev() {
+            nodes[i] = Self::merge(&nodes[i * 2], &nodes[i * 2 + 1]);
+        }
+
+        Self {
+            size,
+            max_size,
+            nodes: nodes.into_boxed_slice(),
+        }
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Counts the elements in the range that are less than or equal to `x`.
+    ///
+    /// # Time Complexity
+    /// O(log² n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn count_leq<R: RangeBounds<usize>>(&self, range: R, x: &T) -> usize {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        self.count_leq_in(left, right, x)
+    }
+
+    /// Returns the `k`-th smallest element (0-based) in the range, or `None`
+    /// if the range holds `k` or fewer elements.
+    ///
+    /// # Time Complexity
+    /// O(log³ n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn kth_in_range<R: RangeBounds<usize>>(&self, range: R, k: usize) -> Option<T> {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        if k >= right - left {
+            return None;
+        }
+
+        // Binary search the smallest value whose count reaches k + 1
+        let sorted = &self.nodes[1];
+        let index = sorted.partition_point(|v| self.count_leq_in(left, right, v) <= k);
+        sorted.get(index).cloned()
+    }
+
+    // ===== PRIVATE HELPER METHODS =====
+
+    fn count_leq_in(&self, left: usize, right: usize, x: &T) -> usize {
+        let mut left = left + self.max_size;
+        let mut right = right + self.max_size;
+        let mut count = 0;
+
+        while left < right {
+            if left & 1 == 1 {
+                count += self.nodes[left].partition_point(|v| v <= x);
+                left += 1;
+            }
+            if right & 1 == 1 {
+                right -= 1;
+                count += self.nodes[right].partition_point(|v| v <= x);
+            }
+            left /= 2;
+            right /= 2;
+        }
+        count
+    }
+
+    fn merge(a: &[T], b: &[T]) -> Vec<T> {
+        let mut merged = Vec::with_capacity(a.len() + b.len());
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            if a[i] <= b[j] {
+                merged.push(a[i].clone());
+                i += 1;
+            } else {
+                merged.push(b[j].clone());
+                j += 1;
+            }
+        }
+        merged.extend_from_slice(&a[i..]);
+        merged.extend_from_slice(&b[j..]);
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_count_leq_basic() {
+        let tree = MergeSortTree::from_vec(vec![5, 1, 4, 2, 3, 3]);
+
+        assert_eq!(tree.count_leq(.., &3), 4);
+        assert_eq!(tree.count_leq(.., &0), 0);
+        assert_eq!(tree.count_leq(.., &10), 6);
+        assert_eq!(tree.count_leq(2..5, &3), 2);
+        assert_eq!(tree.count_leq(3..3, &10), 0);
+    }
+
+    #[test]
+    fn test_kth_in_range_basic() {
+        let tree = MergeSortTree::from_vec(vec![5, 1, 4, 2, 3, 3]);
+
+        assert_eq!(tree.kth_in_range(.., 0), Some(1));
+        assert_eq!(tree.kth_in_range(.., 3), Some(3));
+        assert_eq!(tree.kth_in_range(.., 5), Some(5));
+        assert_eq!(tree.kth_in_range(.., 6), None);
+        assert_eq!(tree.kth_in_range(2..4, 1), Some(4));
+        assert_eq!(tree.kth_in_range(2..2, 0), None);
+    }
+
+    #[test]
+    fn test_random_against_brute_force() {
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let size = rng.random_range(1..50);
+            let values: Vec<i32> = (0..size).map(|_| rng.random_range(-20..20)).collect();
+            let tree = MergeSortTree::from_slice(&values);
+
+            for _ in 0..100 {
+                let l = rng.random_range(0..=size);
+                let r = rng.random_range(l..=size);
+                let x = rng.random_range(-25..25);
+                let expected = values[l..r].iter().filter(|&&v| v <= x).count();
+                assert_eq!(tree.count_leq(l..r, &x), expected);
+
+                let mut sorted = values[l..r].to_vec();
+                sorted.sort();
+                let k = rng.random_range(0..=sorted.len());
+                assert_eq!(tree.kth_in_range(l..r, k), sorted.get(k).copied());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_out_of_bounds() {
+        let tree = MergeSortTree::from_vec(vec![1, 2, 3]);
+        tree.count_leq(..4, &2);
+    }
+}
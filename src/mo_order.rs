@@ -0,0 +1,105 @@
+//! Query ordering for Mo's algorithm.
+//!
+//! Mo's algorithm answers offline range queries by moving two pointers over
+//! the array and adding or removing one element at a time. Visiting the
+//! queries grouped by the `√n`-sized block of their left endpoint, and by
+//! right endpoint within a block, bounds the total pointer movement by
+//! O((n + q)√n). [`mo_order`] computes that visiting order; the pointer loop
+//! itself stays in user code.
+//!
+//! # Example
+//!
+//! ```rust
+//! use array_range_query::mo_order;
+//!
+//! let array = [1, 2, 1, 3, 2, 2, 4, 1, 3];
+//! let queries = [6..9, 0..4, 1..3, 2..8];
+//!
+//! // Distinct counts, maintained by moving [l, r) one element at a time
+//! let mut counts = [0usize; 5];
+//! let (mut l, mut r, mut distinct) = (0, 0, 0);
+//! let mut answers = vec![0; queries.len()];
+//! for i in mo_order(&queries, array.len()) {
+//!     let q = &queries[i];
+//!     while r < q.end { counts[array[r]] += 1; distinct += (counts[array[r]] == 1) as usize; r += 1; }
+//!     while l > q.start { l -= 1; counts[array[l]] += 1; distinct += (counts[array[l]] == 1) as usize; }
+//!     while r > q.end { r -= 1; counts[array[r]] -= 1; distinct -= (counts[array[r]] == 0) as usize; }
+//!     while l < q.start { counts[array[l]] -= 1; distinct -= (counts[array[l]] == 0) as usize; l += 1; }
+//!     answers[i] = distinct;
+//! }
+//! assert_eq!(answers, vec![3, 3, 2, 4]);
+//! ```
+
+use crate::utils;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Returns the indices of `queries` in Mo's algorithm order for an array of
+/// length `n`.
+///
+/// Queries are sorted by `start / block`, where `block = max(1, ⌊√n⌋)`, then
+/// by `end`, then by their original index, so the order is deterministic.
+///
+/// # Time Complexity
+/// O(q log q) for `q` queries
+///
+/// # Panics
+/// Panics if any range is invalid or out of bounds for `n`.
+pub fn mo_order(queries: &[Range<usize>], n: usize) -> Vec<usize> {
+    for query in queries {
+        utils::validate_range(query.start, query.end, n);
+    }
+
+    let block = n.isqrt().max(1);
+    let mut order: Vec<usize> = (0..queries.len()).collect();
+    order.sort_unstable_by_key(|&i| (queries[i].start / block, queries[i].end, i));
+    order
+}
+
+// ===== TESTS =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_left_endpoints_into_sqrt_blocks() {
+        // n = 100 gives blocks of 10
+        let queries = [
+            25..40,
+            3..90,
+            12..13,
+            9..10,
+            10..50,
+            0..20,
+            29..30,
+            95..100,
+            20..21,
+        ];
+        let order = mo_order(&queries, 100);
+        assert_eq!(order, vec![3, 5, 1, 2, 4, 8, 6, 0, 7]);
+
+        let blocks: Vec<usize> = order.iter().map(|&i| queries[i].start / 10).collect();
+        assert!(blocks.windows(2).all(|w| w[0] <= w[1]));
+        for pair in order.windows(2) {
+            let (a, b) = (&queries[pair[0]], &queries[pair[1]]);
+            if a.start / 10 == b.start / 10 {
+                assert!(a.end <= b.end);
+            }
+        }
+    }
+
+    #[test]
+    fn small_and_empty_inputs() {
+        assert!(mo_order(&[], 0).is_empty());
+        assert_eq!(mo_order(&[0..0, 0..0], 0), vec![0, 1]);
+        // Blocks of 1 sort by start, then end
+        assert_eq!(mo_order(&[2..3, 0..3, 0..1, 1..2], 3), vec![2, 1, 3, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn rejects_out_of_bounds_query() {
+        mo_order(&[1..2, 0..5], 4);
+    }
+}
@@ -0,0 +1,162 @@
+//! Offline counting of elements by value within index ranges.
+//!
+//! [`RangeValueCountTree`] answers "how many elements in `[l, r)` have a value
+//! in `[lo, hi]`" for a batch of queries. Each answer is split into
+//! `count(value <= hi) - count(value < lo)` over `[l, r)`, and both halves are
+//! computed by sweeping the elements in value order while a
+//! [`FenwickTree`] marks the positions swept so far, so every count is an
+//! index range sum.
+//!
+//! # Example
+//!
+//! ```rust
+//! use array_range_query::RangeValueCountTree;
+//!
+//! let array = [5, 1, 4, 2, 3, 4];
+//! let queries = [(0..4, 2..=4), (2..6, 4..=4), (1..1, 0..=9)];
+//! let answers = RangeValueCountTree::answer_offline(&array, &queries);
+//! assert_eq!(answers, vec![2, 2, 0]);
+//! ```
+
+use crate::{utils, FenwickTree};
+use alloc::vec::Vec;
+use core::ops::{Range, RangeInclusive};
+
+/// Offline solver for value-range counts over many index ranges.
+pub struct RangeValueCountTree;
+
+impl RangeValueCountTree {
+    /// Returns, for each `(index_range, value_range)` query, the number of
+    /// elements of `array` in `index_range` whose value lies in
+    /// `value_range`. A value range with `lo > hi` counts nothing.
+    ///
+    /// # Time Complexity
+    /// O((n + q) log n) plus sorting, for `n` elements and `q` queries
+    ///
+    /// # Panics
+    /// Panics if any index range is invalid or out of bounds.
+    pub fn answer_offline<T: Ord>(
+        array: &[T],
+        queries: &[(Range<usize>, RangeInclusive<T>)],
+    ) -> Vec<usize> {
+        for (range, _) in queries {
+            utils::validate_range(range.start, range.end, array.len());
+        }
+
+        let mut by_value: Vec<usize> = (0..array.len()).collect();
+        by_value.sort_by(|&a, &b| array[a].cmp(&array[b]));
+
+        let at_most_hi = Self::count_swept(array, &by_value, queries, |(_, values), v| {
+            v <= values.end()
+        });
+        let below_lo = Self::count_swept(array, &by_value, queries, |(_, values), v| {
+            v < values.start()
+        });
+
+        queries
+            .iter()
+            .zip(at_most_hi.into_iter().zip(below_lo))
+            .map(|((_, values), (hi, lo))| {
+                if values.start() > values.end() {
+                    0
+                } else {
+                    hi - lo
+                }
+            })
+            .collect()
+    }
+
+    /// Counts, for each query, the elements of its index range accepted by
+    /// `accepts`.
+    ///
+    /// `accepts(query, value)` must be a downward-closed threshold on the
+    /// value (once it rejects a value it rejects all larger ones).
+    fn count_swept<T, F>(
+        array: &[T],
+        by_value: &[usize],
+        queries: &[(Range<usize>, RangeInclusive<T>)],
+        accepts: F,
+    ) -> Vec<usize>
+    where
+        T: Ord,
+        F: Fn(&(Range<usize>, RangeInclusive<T>), &T) -> bool,
+    {
+        // Visit queries by increasing threshold: the number of accepted
+        // elements in value order is monotone in it.
+        let threshold = |q: &(Range<usize>, RangeInclusive<T>)| {
+            by_value.partition_point(|&i| accepts(q, &array[i]))
+        };
+        let mut order: Vec<(usize, usize)> = queries
+            .iter()
+            .enumerate()
+            .map(|(i, q)| (threshold(q), i))
+            .collect();
+        order.sort_unstable();
+
+        let mut marks = FenwickTree::<usize>::new(array.len());
+        let mut counts = alloc::vec![0; queries.len()];
+        let mut swept = 0;
+        for (accepted, i) in order {
+            while swept < accepted {
+                marks.add(by_value[swept], 1);
+                swept += 1;
+            }
+            counts[i] = marks.range_sum(queries[i].0.clone());
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn brute_force(array: &[i32], range: Range<usize>, values: &RangeInclusive<i32>) -> usize {
+        array[range].iter().filter(|v| values.contains(v)).count()
+    }
+
+    #[test]
+    fn test_small_exhaustive() {
+        let array = [3, -1, 4, 1, 5, 9, 2, 6, 5, 3];
+        let mut queries = Vec::new();
+        for l in 0..=array.len() {
+            for r in l..=array.len() {
+                for (lo, hi) in [(-5, 20), (3, 5), (5, 3), (4, 4), (10, 12)] {
+                    queries.push((l..r, lo..=hi));
+                }
+            }
+        }
+        let answers = RangeValueCountTree::answer_offline(&array, &queries);
+        for ((range, values), answer) in queries.into_iter().zip(answers) {
+            assert_eq!(answer, brute_force(&array, range, &values));
+        }
+    }
+
+    #[test]
+    fn test_random_against_brute_force() {
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let size = rng.random_range(0..25);
+            let array: Vec<i32> = (0..size).map(|_| rng.random_range(-5..5)).collect();
+            let queries: Vec<_> = (0..50)
+                .map(|_| {
+                    let l = rng.random_range(0..=size);
+                    let r = rng.random_range(l..=size);
+                    let lo = rng.random_range(-6..6);
+                    (l..r, lo..=rng.random_range(lo - 1..7))
+                })
+                .collect();
+            let answers = RangeValueCountTree::answer_offline(&array, &queries);
+            for ((range, values), &answer) in queries.iter().zip(&answers) {
+                assert_eq!(answer, brute_force(&array, range.clone(), values));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_index_range_out_of_bounds() {
+        RangeValueCountTree::answer_offline(&[1, 2, 3], &[(0..4, 1..=2)]);
+    }
+}
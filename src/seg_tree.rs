@@ -23,12 +23,15 @@
 //! ```
 
 use crate::utils;
+use crate::SegTreeNode;
+use alloc::{borrow::Cow, boxed::Box, vec, vec::Vec};
 use core::marker::PhantomData;
-use core::ops::RangeBounds;
+use core::ops::{Range, RangeBounds, RangeInclusive};
 
 /// Specification for segment tree operations.
 ///
-/// Defines an associative operation (monoid) with identity element.
+/// Defines an associative operation (monoid) with identity element, given
+/// either as the constant `ID` or by overriding `id()`.
 /// Must satisfy: `op(a, ID) = a` and `op(a, op(b, c)) = op(op(a, b), c)`.
 ///
 /// # Example
@@ -47,12 +50,242 @@ pub trait SegTreeSpec {
     type T: Clone;
 
     /// Identity element for the operation.
-    const ID: Self::T;
+    ///
+    /// Specs whose identity cannot be built in a `const` context can leave
+    /// this out and override [`id`](Self::id) instead. The default fails to
+    /// compile when it is actually used, so one of the two must be provided.
+    const ID: Self::T = panic!("SegTreeSpec requires either `ID` or `id()` to be implemented");
+
+    /// Returns the identity element; the trees call this instead of reading
+    /// [`ID`](Self::ID) directly.
+    ///
+    /// Defaults to `Self::ID`. Override it for identities that are only known
+    /// at runtime or need allocation, such as an empty `String`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use array_range_query::{SegTree, SegTreeSpec};
+    ///
+    /// struct ConcatSpec;
+    /// impl SegTreeSpec for ConcatSpec {
+    ///     type T = String;
+    ///     fn id() -> Self::T { String::new() }
+    ///     fn op(a: &mut Self::T, b: &Self::T) { a.push_str(b); }
+    /// }
+    ///
+    /// let words = ["seg", "ment", " ", "tree"].map(String::from);
+    /// let tree = SegTree::<ConcatSpec>::from_slice(&words);
+    /// assert_eq!(tree.query(..2), "segment");
+    /// assert_eq!(tree.query(1..), "ment tree");
+    /// ```
+    #[inline]
+    fn id() -> Self::T {
+        Self::ID
+    }
 
     /// Associative binary operation, performed in-place.
     ///
     /// Modifies `a` to store the result of combining `a` with `b`.
     fn op(a: &mut Self::T, b: &Self::T);
+
+    /// Associative binary operation on owned values.
+    ///
+    /// Used where `b` is a temporary, e.g. when merging query accumulators.
+    /// Defaults to [`op`](Self::op); override it when moving out of `b` is
+    /// cheaper than reading it through a reference (for example to avoid
+    /// cloning `b` when it replaces `a`).
+    ///
+    /// # Example
+    /// ```rust
+    /// use array_range_query::SegTreeSpec;
+    ///
+    /// struct LongestSpec;
+    /// impl SegTreeSpec for LongestSpec {
+    ///     type T = Vec<u8>;
+    ///     fn id() -> Self::T { Vec::new() }
+    ///     fn op(a: &mut Self::T, b: &Self::T) {
+    ///         if b.len() > a.len() { *a = b.clone(); }
+    ///     }
+    ///     fn op_owned(a: Self::T, b: Self::T) -> Self::T {
+    ///         if b.len() > a.len() { b } else { a }
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    fn op_owned(mut a: Self::T, b: Self::T) -> Self::T {
+        Self::op(&mut a, &b);
+        a
+    }
+
+    /// Validation hook called with the initial values by the constructors in
+    /// debug builds.
+    ///
+    /// It is also called with each value passed to `update`, `replace`,
+    /// `push`, `fill` and `resize`, and with each padding slot on its own after rebuilds, so an
+    /// `op` that stops treating the identity as neutral is caught too.
+    ///
+    /// Defaults to a no-op. Override it to call
+    /// [`check_monoid_laws`](Self::check_monoid_laws) when `T: PartialEq + Debug`
+    /// to catch a wrong `ID` or a non-associative `op` early.
+    ///
+    /// # Example
+    /// ```rust
+    /// use array_range_query::SegTreeSpec;
+    ///
+    /// struct SumSpec;
+    /// impl SegTreeSpec for SumSpec {
+    ///     type T = i32;
+    ///     const ID: Self::T = 0;
+    ///     fn op(a: &mut Self::T, b: &Self::T) { *a += *b; }
+    ///     fn debug_check(values: &[Self::T]) { Self::check_monoid_laws(values); }
+    /// }
+    /// ```
+    fn debug_check(_values: &[Self::T]) {}
+
+    /// Asserts the identity and associativity laws on a sample of `values`.
+    ///
+    /// First checks that the root a tree would build from `values`, padded
+    /// with `ID` up to a power of two, equals their plain left fold. This is
+    /// the check that catches a wrong `ID` in the common case, since padding
+    /// is the only place the tree combines values with `ID`. It takes one
+    /// linear pass over `values` and O(log n) extra space.
+    ///
+    /// Then checks `op(a, ID) == a`, `op(ID, a) == a` and
+    /// `op(op(a, b), c) == op(a, op(b, c))` for a bounded, deterministic
+    /// sample of elements.
+    ///
+    /// # Panics
+    /// Panics with the offending values if a law is violated.
+    fn check_monoid_laws(values: &[Self::T])
+    where
+        Self::T: PartialEq + core::fmt::Debug,
+    {
+        const SAMPLES: usize = 32;
+        if values.is_empty() {
+            return;
+        }
+
+        let mut fold = values[0].clone();
+        for v in &values[1..] {
+            Self::op(&mut fold, v);
+        }
+        // Merge equal-height subtrees like a binary counter, so only one
+        // partial aggregate per level is held at a time
+        let mut stack: Vec<(Self::T, u32)> = Vec::new();
+        let padding = values.len().next_power_of_two() - values.len();
+        for leaf in values
+            .iter()
+            .cloned()
+            .chain(core::iter::repeat_n(Self::id(), padding))
+        {
+            let (mut node, mut height) = (leaf, 0);
+            while stack.last().is_some_and(|&(_, h)| h == height) {
+                let (left, _) = stack.pop().unwrap();
+                node = Self::op_owned(left, node);
+                height += 1;
+            }
+            stack.push((node, height));
+        }
+        let root = stack.pop().unwrap().0;
+        assert!(
+            root == fold,
+            "SegTreeSpec identity violated: the tree root over {} values starting with {:?}, padded with ID = {:?}, is {:?}, but their left fold is {:?}; check that ID is neutral for op",
+            values.len(),
+            &values[..values.len().min(4)],
+            Self::id(),
+            root,
+            fold
+        );
+
+        // Simple LCG so sampling needs no dependency and stays reproducible
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut pick = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            &values[(state >> 33) as usize % values.len()]
+        };
+
+        for _ in 0..SAMPLES.min(values.len() * values.len()) {
+            let (a, b, c) = (pick(), pick(), pick());
+
+            let mut right_id = a.clone();
+            Self::op(&mut right_id, &Self::id());
+            let mut left_id = Self::id();
+            Self::op(&mut left_id, a);
+            assert!(
+                right_id == *a && left_id == *a,
+                "SegTreeSpec identity violated: op(a, ID) = {:?}, op(ID, a) = {:?} for a = {:?}",
+                right_id,
+                left_id,
+                a
+            );
+
+            let mut left_first = a.clone();
+            Self::op(&mut left_first, b);
+            Self::op(&mut left_first, c);
+            let mut bc = b.clone();
+            Self::op(&mut bc, c);
+            let mut right_first = a.clone();
+            Self::op(&mut right_first, &bc);
+            assert!(
+                left_first == right_first,
+                "SegTreeSpec associativity violated: op(op(a, b), c) = {:?} but op(a, op(b, c)) = {:?} for a = {:?}, b = {:?}, c = {:?}",
+                left_first,
+                right_first,
+                a,
+                b,
+                c
+            );
+        }
+    }
+}
+
+/// A position in a [`SegTree`]'s undo log, created by
+/// [`SegTree::checkpoint`] and consumed by [`SegTree::rollback`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    epoch: usize,
+    len: usize,
+}
+
+/// A copy of a [`SegTree`]'s elements, created by [`SegTree::snapshot`] and
+/// compared against later with [`SegTree::changed_since`].
+#[derive(Clone, Debug)]
+pub struct Snapshot<T> {
+    values: Vec<T>,
+}
+
+/// Undo log of `(leaf index, previous value)` pairs.
+///
+/// `epoch` is bumped whenever the log is discarded, so checkpoints taken
+/// before that are detected as stale.
+#[derive(Clone)]
+struct Journal<T> {
+    active: bool,
+    epoch: usize,
+    entries: Vec<(usize, T)>,
+}
+
+impl<T> Default for Journal<T> {
+    fn default() -> Self {
+        Self {
+            active: false,
+            epoch: 0,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T> Journal<T> {
+    /// Drops all entries and invalidates every outstanding checkpoint.
+    fn reset(&mut self) {
+        if self.active || !self.entries.is_empty() {
+            self.active = false;
+            self.epoch += 1;
+            self.entries.clear();
+        }
+    }
 }
 
 /// A generic Segment Tree data structure.
@@ -67,6 +300,9 @@ pub trait SegTreeSpec {
 /// - Leaf nodes start at index `max_size` (next power of 2 ≥ `size`)
 /// - For any node at index `i`, its children are at `2*i` and `2*i+1`
 /// - Total space used is `2 * max_size`
+/// - An empty tree keeps a single identity leaf (`max_size == 1`), so the
+///   root at index 1 always exists and [`push`](SegTree::push) has room; its
+///   `len()` is still 0 and every non-empty range is rejected
 ///
 /// # Type Parameters
 ///
@@ -95,6 +331,8 @@ pub struct SegTree<Spec: SegTreeSpec> {
     max_size: usize,
     /// Tree data stored as a flat boxed slice using 1-based indexing
     data: Box<[Spec::T]>,
+    /// Undo log of point updates, active after the first `checkpoint`
+    journal: Journal<Spec::T>,
     /// Zero-sized marker to associate the `Spec` type with the struct
     _spec: PhantomData<Spec>,
 }
@@ -102,7 +340,7 @@ pub struct SegTree<Spec: SegTreeSpec> {
 impl<Spec: SegTreeSpec> SegTree<Spec> {
     // ===== CONSTRUCTORS =====
 
-    /// Creates a new segment tree with all elements initialized to `Spec::ID`.
+    /// Creates a new segment tree with all elements initialized to `Spec::id()`.
     ///
     /// # Time Complexity
     /// O(n)
@@ -111,7 +349,39 @@ impl<Spec: SegTreeSpec> SegTree<Spec> {
         Self {
             size,
             max_size,
-            data: vec![Spec::ID; max_size * 2].into_boxed_slice(),
+            data: vec![Spec::id(); max_size * 2].into_boxed_slice(),
+            journal: Journal::default(),
+            _spec: PhantomData,
+        }
+    }
+
+    /// Creates an empty segment tree with room for at least `capacity`
+    /// elements before [`push`](Self::push) has to reallocate.
+    ///
+    /// The capacity is rounded up to the next power of two, since that is the
+    /// number of leaves the layout holds; backing storage is twice that.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::with_capacity(100); // 128 leaves
+    /// for i in 0..100 {
+    ///     tree.push(i);
+    /// }
+    /// assert_eq!(tree.query(..), 4950);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(capacity)
+    pub fn with_capacity(capacity: usize) -> Self {
+        let max_size = capacity.next_power_of_two();
+        Self {
+            size: 0,
+            max_size,
+            data: vec![Spec::id(); max_size * 2].into_boxed_slice(),
+            journal: Journal::default(),
             _spec: PhantomData,
         }
     }
@@ -121,9 +391,16 @@ impl<Spec: SegTreeSpec> SegTree<Spec> {
     /// # Time Complexity
     /// O(n)
     pub fn from_slice(values: &[Spec::T]) -> Self {
+        if values.is_empty() {
+            return Self::new(0);
+        }
+
+        #[cfg(debug_assertions)]
+        Spec::debug_check(values);
+
         let size = values.len();
         let max_size = size.next_power_of_two();
-        let mut data = vec![Spec::ID; 2 * max_size];
+        let mut data = vec![Spec::id(); 2 * max_size];
 
         // Copy initial values to the leaf nodes
         data[max_size..(max_size + size)].clone_from_slice(values);
@@ -135,12 +412,18 @@ impl<Spec: SegTreeSpec> SegTree<Spec> {
             data[i] = v;
         }
 
-        Self {
+        let tree = Self {
             size,
             max_size,
             data: data.into_boxed_slice(),
+            journal: Journal::default(),
             _spec: PhantomData,
-        }
+        };
+
+        #[cfg(debug_assertions)]
+        tree.debug_check_padding();
+
+        tree
     }
 
     /// Creates a new segment tree from a vector of values.
@@ -148,10 +431,17 @@ impl<Spec: SegTreeSpec> SegTree<Spec> {
     /// # Time Complexity
     /// O(n)
     pub fn from_vec(vec: Vec<Spec::T>) -> Self {
+        if vec.is_empty() {
+            return Self::new(0);
+        }
+
+        #[cfg(debug_assertions)]
+        Spec::debug_check(&vec);
+
         let size = vec.len();
         let max_size = size.next_power_of_two();
         // Allocate full tree storage (internal nodes + leaves)
-        let mut data = vec![Spec::ID; 2 * max_size];
+        let mut data = vec![Spec::id(); 2 * max_size];
 
         // Move owned values directly into the leaf slots to avoid cloning
         for (i, v) in vec.into_iter().enumerate() {
@@ -165,153 +455,2414 @@ impl<Spec: SegTreeSpec> SegTree<Spec> {
             data[i] = v;
         }
 
-        Self {
+        let tree = Self {
             size,
             max_size,
             data: data.into_boxed_slice(),
+            journal: Journal::default(),
             _spec: PhantomData,
-        }
-    }
+        };
 
-    // ===== PUBLIC INTERFACE =====
+        #[cfg(debug_assertions)]
+        tree.debug_check_padding();
 
-    /// Queries the aggregated value over the given range.
+        tree
+    }
+
+    /// Creates a new segment tree that takes ownership of a boxed slice of
+    /// leaves.
+    ///
+    /// The buffer is grown into the tree layout through `Vec`'s reallocation,
+    /// which can extend it in place, and the leaves are rotated into position
+    /// instead of being moved into a fresh allocation.
     ///
     /// # Example
     ///
     /// ```
-    /// use array_range_query::helpers::SegTreeMax;
+    /// use array_range_query::SegTreeSum;
     ///
-    /// let mut tree = SegTreeMax::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
-    /// assert_eq!(tree.query(..), 5);
+    /// let leaves: Box<[i32]> = Box::new([1, 2, 3, 4, 5]);
+    /// let tree = SegTreeSum::<i32>::from_boxed_leaves(leaves);
+    /// assert_eq!(tree.query(1..4), 9);
     /// ```
     ///
     /// # Time Complexity
-    /// O(log n)
-    ///
-    /// # Panics
-    /// Panics if the range is invalid or out of bounds.
-    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> Spec::T {
-        let (left, right) = utils::parse_range(range, self.size);
-        utils::validate_range(left, right, self.size);
+    /// O(n)
+    pub fn from_boxed_leaves(leaves: Box<[Spec::T]>) -> Self {
+        #[cfg(debug_assertions)]
+        Spec::debug_check(&leaves);
 
-        if left == right {
-            return Spec::ID;
+        let size = leaves.len();
+        let max_size = size.next_power_of_two();
+        let mut data = leaves.into_vec();
+        data.resize(2 * max_size, Spec::id());
+        // Leaves sit at the front; move them to `max_size..max_size + size`
+        data.rotate_right(max_size);
+
+        for i in (1..max_size).rev() {
+            let mut v = data[i * 2].clone();
+            Spec::op(&mut v, &data[i * 2 + 1]);
+            data[i] = v;
         }
 
-        // Map the logical range to the internal array indices
-        let mut left = left + self.max_size;
-        let mut right = right + self.max_size;
+        let tree = Self {
+            size,
+            max_size,
+            data: data.into_boxed_slice(),
+            journal: Journal::default(),
+            _spec: PhantomData,
+        };
 
-        // Initialize accumulators for the left and right sides of the range
-        let mut result_left = Spec::ID;
-        let mut result_right = Spec::ID;
+        #[cfg(debug_assertions)]
+        tree.debug_check_padding();
 
-        // Standard segment tree range query algorithm
-        while left < right {
-            // If left is odd (right child), include it and move to next
-            if left & 1 == 1 {
-                Spec::op(&mut result_left, &self.data[left]);
-                left += 1;
-            }
-            // If right is odd (right child), include the left sibling and move back
-            if right % 2 == 1 {
-                right -= 1;
-                Spec::op(&mut result_right, &self.data[right]);
-            }
-            // Move up to parent level
-            left /= 2;
-            right /= 2;
+        tree
+    }
+
+    /// Creates a new segment tree from a vector of values, building internal
+    /// nodes in parallel.
+    ///
+    /// Nodes at the same depth are independent, so each level is computed
+    /// with rayon before moving up to the next one. The result is identical
+    /// to [`from_vec`](Self::from_vec); this only pays off for large inputs.
+    ///
+    /// Requires the `rayon` feature.
+    ///
+    /// # Time Complexity
+    /// O(n) work, O(log n) levels of parallel steps
+    #[cfg(feature = "rayon")]
+    pub fn from_vec_parallel(vec: Vec<Spec::T>) -> Self
+    where
+        Spec::T: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        #[cfg(debug_assertions)]
+        Spec::debug_check(&vec);
+
+        let size = vec.len();
+        let max_size = size.next_power_of_two();
+        let mut data = Vec::with_capacity(2 * max_size);
+        data.resize(max_size, Spec::id());
+        data.extend(vec);
+        data.resize(2 * max_size, Spec::id());
+
+        // Build one level at a time, from the deepest internal level to the root
+        let mut level_start = max_size / 2;
+        while level_start >= 1 {
+            let (parents, children) = data.split_at_mut(level_start * 2);
+            parents[level_start..]
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(i, parent)| {
+                    let mut v = children[i * 2].clone();
+                    Spec::op(&mut v, &children[i * 2 + 1]);
+                    *parent = v;
+                });
+            level_start /= 2;
         }
 
-        // Combine the left and right results
-        Spec::op(&mut result_left, &result_right);
-        result_left
+        let tree = Self {
+            size,
+            max_size,
+            data: data.into_boxed_slice(),
+            journal: Journal::default(),
+            _spec: PhantomData,
+        };
+
+        #[cfg(debug_assertions)]
+        tree.debug_check_padding();
+
+        tree
     }
 
-    /// Updates the value at the given index.
+    /// Creates a new segment tree from owned or borrowed values.
+    ///
+    /// Owned vectors are moved into the tree like [`from_vec`](Self::from_vec);
+    /// borrowed slices are cloned like [`from_slice`](Self::from_slice).
     ///
     /// # Example
     ///
     /// ```
-    /// use array_range_query::helpers::SegTreeMax;
+    /// use array_range_query::SegTreeSum;
     ///
-    /// let mut tree = SegTreeMax::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
-    /// assert_eq!(tree.query(..), 5);
-    /// tree.update(2, 6);
-    /// assert_eq!(tree.query(..), 6);
+    /// let values = vec![1, 2, 3];
+    /// let borrowed = SegTreeSum::<i32>::build(&values[..]);
+    /// let owned = SegTreeSum::<i32>::build(values);
+    /// assert_eq!(borrowed.query(..), owned.query(..));
     /// ```
     ///
     /// # Time Complexity
-    /// O(log n)
-    ///
-    /// # Panics
-    /// Panics if `index` is out of bounds.
-    pub fn update(&mut self, index: usize, value: Spec::T) {
-        assert!(index < self.size, "update index out of bounds");
-
-        let leaf_index = index + self.max_size;
-        self.data[leaf_index] = value;
-        self.recompute(leaf_index);
+    /// O(n)
+    pub fn build<'a>(values: impl Into<Cow<'a, [Spec::T]>>) -> Self
+    where
+        Spec::T: 'a,
+    {
+        match values.into() {
+            Cow::Borrowed(slice) => Self::from_slice(slice),
+            Cow::Owned(vec) => Self::from_vec(vec),
+        }
     }
 
-    // ===== PRIVATE HELPER METHODS =====
-
-    /// Recomputes parent nodes from a leaf up to the root.
-    fn recompute(&mut self, mut index: usize) {
-        // Move up the tree level by level
-        while index > 1 {
-            index /= 2; // Move to parent
+    // ===== PUBLIC INTERFACE =====
 
-            // Recompute parent value from its two children
-            let mut v = self.data[index * 2].clone();
-            Spec::op(&mut v, &self.data[index * 2 + 1]);
-            self.data[index] = v;
-        }
+    /// Returns the number of elements in the tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let values = vec![1, 2, 3, 4, 5];
+    /// let tree = SegTreeSum::<i32>::from_slice(&values);
+    /// assert_eq!(tree.len(), values.len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.size
     }
-}
-
-// ===== TESTS =====
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Returns `true` if the tree holds no elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// assert!(SegTreeSum::<i32>::new(0).is_empty());
+    /// assert!(!SegTreeSum::<i32>::new(3).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
 
-    /// Test specification for sum operations.
-    struct SumSpec;
-    impl SegTreeSpec for SumSpec {
-        type T = i64;
-        const ID: Self::T = 0;
+    /// Returns the number of leaves in the internal layout, i.e. the next
+    /// power of two of [`len`](Self::len).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// assert_eq!(SegTreeSum::<i32>::new(5).capacity(), 8);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.max_size
+    }
 
-        fn op(a: &mut Self::T, b: &Self::T) {
-            *a += *b;
-        }
+    /// Returns the number of levels below the root, i.e. `log2(capacity())`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// assert_eq!(SegTreeSum::<i32>::new(5).height(), 3);
+    /// assert_eq!(SegTreeSum::<i32>::new(1).height(), 0);
+    /// ```
+    pub fn height(&self) -> u32 {
+        self.max_size.trailing_zeros()
     }
 
-    #[test]
-    fn test_new_empty() {
-        let seg_tree = SegTree::<SumSpec>::new(10);
-        assert_eq!(seg_tree.query(..), 0);
+    /// Returns an estimate of the bytes held by the node storage,
+    /// `2 * capacity() * size_of::<T>()`.
+    ///
+    /// Heap memory owned by the elements themselves and the undo log are not
+    /// counted. With power-of-two padding this is up to twice what the same
+    /// number of elements would take at an exact size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// assert_eq!(SegTreeSum::<i64>::new(5).mem_bytes(), 16 * 8);
+    /// ```
+    pub fn mem_bytes(&self) -> usize {
+        core::mem::size_of_val(&*self.data)
     }
 
-    #[test]
-    fn test_from_slice_with_query() {
-        let values = vec![1, 2, 3];
-        let seg_tree = SegTree::<SumSpec>::from_slice(&values);
+    /// Returns the length of the flat backing array, `2 * capacity()`.
+    ///
+    /// Layout-dependent: node `i` (1-based, root at 1) lives at index `i`,
+    /// and index 0 is unused. Intended for custom traversals over the
+    /// internal layout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::new(5);
+    /// assert_eq!(tree.internal_len(), 16);
+    /// ```
+    pub fn internal_len(&self) -> usize {
+        self.data.len()
+    }
 
-        // Comprehensively test if querying works correctly for any range
-        assert_eq!(seg_tree.query(0..1), 1);
-        assert_eq!(seg_tree.query(1..2), 2);
-        assert_eq!(seg_tree.query(2..3), 3);
-        assert_eq!(seg_tree.query(..2), 3);
-        assert_eq!(seg_tree.query(1..), 5);
-        assert_eq!(seg_tree.query(..), 6);
+    /// Returns the index of the first leaf in the flat backing array; element
+    /// `i` is stored at `leaf_offset() + i`.
+    ///
+    /// Layout-dependent, equal to [`capacity`](Self::capacity) for the
+    /// power-of-two layout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::{SegTreeNode, SegTreeSum};
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// let leaf = SegTreeNode(tree.leaf_offset() + 2);
+    /// assert_eq!(tree.canonical_nodes(2..3), vec![leaf]);
+    /// ```
+    pub fn leaf_offset(&self) -> usize {
+        self.max_size
     }
 
-    #[test]
-    fn test_from_vec_with_query() {
-        let values = vec![1, 2, 3];
-        let seg_tree = SegTree::<SumSpec>::from_vec(values);
+    /// Queries the aggregated value over the given range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::helpers::SegTreeMax;
+    ///
+    /// let mut tree = SegTreeMax::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.query(..), 5);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> Spec::T {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        self.query_in(left, right)
+    }
+
+    /// Queries the aggregated value over the given range, returning `None`
+    /// instead of panicking if the range is invalid or out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.try_query(1..4), Some(9));
+    /// assert_eq!(tree.try_query(..6), None);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    pub fn try_query<R: RangeBounds<usize>>(&self, range: R) -> Option<Spec::T> {
+        let (left, right) = utils::try_parse_range(range, self.size)?;
+        Some(self.query_in(left, right))
+    }
+
+    /// Returns the aggregate of every element, the same as `query(..)`.
+    ///
+    /// Reads the root directly, since padding leaves hold the identity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.total(), 15);
+    /// assert_eq!(tree.total(), tree.query(..));
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(1)
+    pub fn total(&self) -> Spec::T {
+        self.data[1].clone()
+    }
+
+    /// Combines two partial results the same way the tree does, returning
+    /// `op(a, b)`.
+    ///
+    /// `a` is the aggregate of the elements on the left and `b` of those on
+    /// the right, so for adjacent ranges `combine(&query(l..m), &query(m..r))`
+    /// equals `query(l..r)`. The order matters for non-commutative specs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// let left = tree.query(..2);
+    /// let right = tree.query(2..);
+    /// assert_eq!(SegTreeSum::<i32>::combine(&left, &right), tree.query(..));
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(1) calls to `op`
+    pub fn combine(a: &Spec::T, b: &Spec::T) -> Spec::T {
+        let mut v = a.clone();
+        Spec::op(&mut v, b);
+        v
+    }
+
+    /// Queries the aggregated value over the first `end` elements, the same
+    /// as `query(..end)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.prefix(3), 6);
+    /// assert_eq!(tree.prefix(0), 0);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `end` exceeds the tree size.
+    pub fn prefix(&self, end: usize) -> Spec::T {
+        utils::validate_range(0, end, self.size);
+        self.query_in(0, end)
+    }
+
+    /// Queries the aggregated value from `start` to the end, the same as
+    /// `query(start..)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.suffix(3), 9);
+    /// assert_eq!(tree.suffix(5), 0);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `start` exceeds the tree size.
+    pub fn suffix(&self, start: usize) -> Spec::T {
+        utils::validate_range(start, self.size, self.size);
+        self.query_in(start, self.size)
+    }
+
+    /// Queries the aggregated value over the `len` elements starting at
+    /// `start`, i.e. `start..start + len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.query_len(1, 3), 9);
+    /// assert_eq!(tree.query_len(4, 0), 0);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `start + len` overflows or exceeds the tree size.
+    pub fn query_len(&self, start: usize, len: usize) -> Spec::T {
+        let (left, right) = utils::parse_len_range(start, len, self.size);
+        self.query_in(left, right)
+    }
+
+    /// Queries `start..end` where negative indices count back from the end,
+    /// as in Python slicing: `-1` is `len() - 1` and `-len()` is `0`.
+    ///
+    /// Non-negative indices are used as is. Unlike Python, indices are not
+    /// clamped: each must resolve to a position in `0..=len()`, and the
+    /// resolved start must not exceed the resolved end.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.query_rel(-3, -1), 3 + 4); // query(2..4)
+    /// assert_eq!(tree.query_rel(1, -1), 2 + 3 + 4);
+    /// assert_eq!(tree.query_rel(-2, 5), 4 + 5);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if either index resolves outside `0..=len()` or the resolved
+    /// start is after the resolved end.
+    pub fn query_rel(&self, start: isize, end: isize) -> Spec::T {
+        let left = utils::resolve_rel_index(start, self.size);
+        let right = utils::resolve_rel_index(end, self.size);
+        utils::validate_range(left, right, self.size);
+        self.query_in(left, right)
+    }
+
+    /// Queries the range with its elements combined from right to left, i.e.
+    /// `op(a[r - 1], op(..., a[l]))` for the range `l..r`.
+    ///
+    /// For a commutative `op` this equals [`query`](Self::query). For a
+    /// non-commutative one (matrix products, concatenation, hashes) it is the
+    /// aggregate of the reversed range. Stored nodes only hold left-to-right
+    /// aggregates, so the leaves are folded one by one; keep a second tree
+    /// over the reversed array if this is needed on large ranges.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::{SegTree, SegTreeSpec};
+    ///
+    /// struct Concat;
+    /// impl SegTreeSpec for Concat {
+    ///     type T = String;
+    ///     fn id() -> String { String::new() }
+    ///     fn op(a: &mut String, b: &String) { a.push_str(b); }
+    /// }
+    ///
+    /// let tree = SegTree::<Concat>::from_vec(["a", "b", "c"].map(String::from).to_vec());
+    /// assert_eq!(tree.query(..), "abc");
+    /// assert_eq!(tree.query_rev(..), "cba");
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(r - l)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query_rev<R: RangeBounds<usize>>(&self, range: R) -> Spec::T {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+
+        let leaves = &self.data[self.max_size + left..self.max_size + right];
+        let mut rev = leaves.iter().rev();
+        match rev.next() {
+            Some(last) => rev.fold(last.clone(), |mut acc, v| {
+                Spec::op(&mut acc, v);
+                acc
+            }),
+            None => Spec::id(),
+        }
+    }
+
+    /// Answers a batch of range queries, returning the results in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.query_batch(&[0..2, 1..4, 3..3]), vec![3, 9, 0]);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(q log n) for q ranges
+    ///
+    /// # Panics
+    /// Panics if any range is invalid or out of bounds.
+    pub fn query_batch(&self, ranges: &[Range<usize>]) -> Vec<Spec::T> {
+        ranges
+            .iter()
+            .map(|range| self.query(range.clone()))
+            .collect()
+    }
+
+    /// Returns the canonical nodes whose union is exactly the given range,
+    /// in left-to-right order.
+    ///
+    /// These are the O(log n) disjoint nodes that [`query`](Self::query)
+    /// combines. Bounds of each node follow
+    /// [`SegTreeNode::node_bounds`] with `max_depth = log2(max_size)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::{SegTreeNode, SegTreeSum};
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![0; 8]);
+    /// // [1, 7) = [1, 2) + [2, 4) + [4, 6) + [6, 7)
+    /// assert_eq!(
+    ///     tree.canonical_nodes(1..7),
+    ///     vec![SegTreeNode(9), SegTreeNode(5), SegTreeNode(6), SegTreeNode(14)]
+    /// );
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn canonical_nodes<R: RangeBounds<usize>>(&self, range: R) -> Vec<SegTreeNode> {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+
+        let mut left = left + self.max_size;
+        let mut right = right + self.max_size;
+        let mut nodes = Vec::new();
+        let mut right_nodes = Vec::new();
+
+        while left < right {
+            if left & 1 == 1 {
+                nodes.push(SegTreeNode(left));
+                left += 1;
+            }
+            if right & 1 == 1 {
+                right -= 1;
+                right_nodes.push(SegTreeNode(right));
+            }
+            left /= 2;
+            right /= 2;
+        }
+
+        nodes.extend(right_nodes.into_iter().rev());
+        nodes
+    }
+
+    /// Iterates over the canonical nodes of the range together with their
+    /// stored aggregates.
+    ///
+    /// Nodes are yielded left to right, the same order as
+    /// [`canonical_nodes`](Self::canonical_nodes), so folding the aggregates
+    /// in iteration order with `op` reproduces [`query`](Self::query) even for
+    /// non-commutative operations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// let parts: Vec<i32> = tree.query_segments(1..5).map(|(_, v)| *v).collect();
+    /// assert_eq!(parts, vec![2, 7, 5]); // [1, 2) + [2, 4) + [4, 5)
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query_segments<R: RangeBounds<usize>>(
+        &self,
+        range: R,
+    ) -> impl Iterator<Item = (SegTreeNode, &Spec::T)> + '_ {
+        self.canonical_nodes(range)
+            .into_iter()
+            .map(move |node| (node, &self.data[node.0]))
+    }
+
+    /// Folds the canonical node aggregates of the range into a custom
+    /// accumulator, left to right.
+    ///
+    /// Unlike [`query`](Self::query), this never builds `Spec::id()` nor
+    /// clones stored values, and the accumulator may have any type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeMin;
+    ///
+    /// let tree = SegTreeMin::<i32>::from_vec(vec![5, 3, 8, 3, 9]);
+    /// // Minimum of the node minima, and how many nodes were visited
+    /// let (min, nodes) = tree.query_with(1..5, (i32::MAX, 0), |(m, n), &v| (m.min(v), n + 1));
+    /// assert_eq!(min, 3);
+    /// assert_eq!(nodes, 3);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n) calls to `f`
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query_with<R, A, F>(&self, range: R, init: A, mut f: F) -> A
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(A, &Spec::T) -> A,
+    {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+
+        let mut left = left + self.max_size;
+        let mut right = right + self.max_size;
+        let mut acc = init;
+        // At most one right-side node per level; buffered to fold them last
+        let mut right_nodes = [0usize; usize::BITS as usize];
+        let mut right_count = 0;
+
+        while left < right {
+            if left & 1 == 1 {
+                acc = f(acc, &self.data[left]);
+                left += 1;
+            }
+            if right & 1 == 1 {
+                right -= 1;
+                right_nodes[right_count] = right;
+                right_count += 1;
+            }
+            left /= 2;
+            right /= 2;
+        }
+
+        for &node in right_nodes[..right_count].iter().rev() {
+            acc = f(acc, &self.data[node]);
+        }
+        acc
+    }
+
+    /// Returns a reference to the element at `index`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// assert_eq!(*tree.get(1), 2);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(1)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> &Spec::T {
+        assert!(index < self.size, "get index out of bounds");
+        &self.data[index + self.max_size]
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if `index` is
+    /// out of bounds.
+    ///
+    /// # Time Complexity
+    /// O(1)
+    pub fn try_get(&self, index: usize) -> Option<&Spec::T> {
+        (index < self.size).then(|| &self.data[index + self.max_size])
+    }
+
+    /// Queries an inclusive range, rejecting reversed ranges such as `5..=4`.
+    ///
+    /// [`query`](Self::query) treats `5..=4` like the empty range `5..5` and
+    /// returns `Spec::ID`. This variant panics instead, which catches inclusive
+    /// bounds that were computed the wrong way round.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.query_range_inclusive_checked(1..=3), 9);
+    /// assert_eq!(tree.query_range_inclusive_checked(2..=2), 3);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `start > end`, or if the range is out of bounds.
+    pub fn query_range_inclusive_checked(&self, range: RangeInclusive<usize>) -> Spec::T {
+        let (start, end) = range.into_inner();
+        assert!(
+            start <= end,
+            "Invalid range: got reversed inclusive range [{}, {}]",
+            start,
+            end
+        );
+        self.query(start..=end)
+    }
+
+    /// Returns the smallest index `i` such that `query(..=i) >= target`, or
+    /// `len` if no prefix reaches `target`.
+    ///
+    /// This is the "find the k-th item by cumulative weight" primitive. The
+    /// descent assumes prefix aggregates are monotone non-decreasing under
+    /// `op` (e.g. sums of non-negative values); otherwise the result is
+    /// unspecified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<u32>::from_vec(vec![2, 0, 3, 1]);
+    /// assert_eq!(tree.lower_bound(1), 0); // prefix sums: 2, 2, 5, 6
+    /// assert_eq!(tree.lower_bound(3), 2);
+    /// assert_eq!(tree.lower_bound(6), 3);
+    /// assert_eq!(tree.lower_bound(7), 4);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    pub fn lower_bound(&self, target: Spec::T) -> usize
+    where
+        Spec::T: PartialOrd,
+    {
+        if self.data[1] < target {
+            return self.size;
+        }
+
+        let mut node = 1;
+        let mut acc = Spec::id();
+        while node < self.max_size {
+            let mut with_left = acc.clone();
+            Spec::op(&mut with_left, &self.data[node * 2]);
+            if with_left >= target {
+                node *= 2;
+            } else {
+                acc = with_left;
+                node = node * 2 + 1;
+            }
+        }
+        node - self.max_size
+    }
+
+    /// Walks down to the first element of `range` selected by `pred`,
+    /// returning its index, or `None` if `pred` rejects the whole range.
+    ///
+    /// `pred(acc, node)` receives the aggregate `acc` of the range elements
+    /// before a node and the node's own aggregate, and returns whether the
+    /// wanted element lies inside that node. The canonical nodes of `range`
+    /// are tried left to right; inside the first accepted node the walk steps
+    /// into the left child when `pred` accepts it, and otherwise folds the
+    /// left child into `acc` and steps right.
+    ///
+    /// For the result to be meaningful, `pred` must be consistent across
+    /// levels: if it accepts a node, it accepts one of its children given the
+    /// matching `acc`, and if it rejects a node, it would reject every leaf
+    /// below it. Prefix searches such as "the aggregate so far reaches a
+    /// target" satisfy this when prefix aggregates are monotone.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<u32>::from_vec(vec![4, 1, 0, 3, 2, 5]);
+    /// // First index from 1 on where the running sum exceeds 3
+    /// let exceeds = |acc: &u32, node: &u32| acc + node > 3;
+    /// assert_eq!(tree.descend(1.., exceeds), Some(3)); // 1 + 0 + 3
+    /// assert_eq!(tree.descend(1..3, exceeds), None);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n) calls to `pred` and `op`
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn descend<R, F>(&self, range: R, mut pred: F) -> Option<usize>
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&Spec::T, &Spec::T) -> bool,
+    {
+        let mut acc = Spec::id();
+        let mut found = None;
+        for (node, value) in self.query_segments(range) {
+            if pred(&acc, value) {
+                found = Some(node);
+                break;
+            }
+            Spec::op(&mut acc, value);
+        }
+
+        let mut node = found?;
+        while node.0 < self.max_size {
+            let left = node.left_child();
+            node = if pred(&acc, &self.data[left.0]) {
+                left
+            } else {
+                Spec::op(&mut acc, &self.data[left.0]);
+                node.right_child()
+            };
+        }
+        Some(node.0 - self.max_size)
+    }
+
+    /// Updates the value at the given index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::helpers::SegTreeMax;
+    ///
+    /// let mut tree = SegTreeMax::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(tree.query(..), 5);
+    /// tree.update(2, 6);
+    /// assert_eq!(tree.query(..), 6);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn update(&mut self, index: usize, value: Spec::T) {
+        assert!(index < self.size, "update index out of bounds");
+
+        #[cfg(debug_assertions)]
+        Spec::debug_check(core::slice::from_ref(&value));
+
+        let leaf_index = index + self.max_size;
+        let old = core::mem::replace(&mut self.data[leaf_index], value);
+        if self.journal.active {
+            self.journal.entries.push((leaf_index, old));
+        }
+        self.recompute(leaf_index);
+    }
+
+    /// Sets the value at `index` and returns the previous value, like
+    /// [`core::mem::replace`].
+    ///
+    /// Journaled like [`update`](Self::update) while checkpoints are active.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// assert_eq!(tree.replace(1, 20), 2);
+    /// assert_eq!(tree.query(..), 24);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn replace(&mut self, index: usize, value: Spec::T) -> Spec::T {
+        assert!(index < self.size, "replace index out of bounds");
+
+        #[cfg(debug_assertions)]
+        Spec::debug_check(core::slice::from_ref(&value));
+
+        let leaf_index = index + self.max_size;
+        let old = core::mem::replace(&mut self.data[leaf_index], value);
+        if self.journal.active {
+            self.journal.entries.push((leaf_index, old.clone()));
+        }
+        self.recompute(leaf_index);
+        old
+    }
+
+    /// Updates the value at `index` unless it already equals `value`, and
+    /// returns whether the tree changed.
+    ///
+    /// An unchanged leaf costs one comparison instead of the O(log n)
+    /// recomputation and leaves no journal entry, which pays off when many
+    /// updates are redundant. Otherwise this behaves like
+    /// [`update`](Self::update).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// assert!(!tree.update_if_changed(1, 2));
+    /// assert!(tree.update_if_changed(1, 5));
+    /// assert_eq!(tree.query(..), 9);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(1) if the value is unchanged, O(log n) otherwise
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn update_if_changed(&mut self, index: usize, value: Spec::T) -> bool
+    where
+        Spec::T: PartialEq,
+    {
+        assert!(index < self.size, "update index out of bounds");
+        if self.data[index + self.max_size] == value {
+            return false;
+        }
+        self.update(index, value);
+        true
+    }
+
+    /// Exchanges the elements at `i` and `j`.
+    ///
+    /// Both leaf paths are recomputed up to their lowest common ancestor,
+    /// and the shared part of the path above it only once, so this costs
+    /// fewer operations than two [`update`](Self::update) calls.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.swap(0, 3);
+    /// assert_eq!(tree.query(..1), 4);
+    /// assert_eq!(tree.query(3..), 1);
+    /// assert_eq!(tree.query(..), 10);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if `i` or `j` is out of bounds.
+    pub fn swap(&mut self, i: usize, j: usize) {
+        assert!(i < self.size && j < self.size, "swap index out of bounds");
+        if i == j {
+            return;
+        }
+
+        let mut a = i + self.max_size;
+        let mut b = j + self.max_size;
+        if self.journal.active {
+            self.journal.entries.push((a, self.data[a].clone()));
+            self.journal.entries.push((b, self.data[b].clone()));
+        }
+        self.data.swap(a, b);
+
+        // Both leaves sit at the same depth, so their paths meet at one level
+        a /= 2;
+        b /= 2;
+        while a != b {
+            self.pull(a);
+            self.pull(b);
+            a /= 2;
+            b /= 2;
+        }
+        while a >= 1 {
+            self.pull(a);
+            a /= 2;
+        }
+    }
+
+    /// Sets the value at `index`, returning `None` instead of panicking if
+    /// `index` is out of bounds (the value is dropped in that case).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// assert_eq!(tree.try_update(0, 10), Some(()));
+    /// assert_eq!(tree.try_update(3, 10), None);
+    /// assert_eq!(tree.query(..), 15);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    pub fn try_update(&mut self, index: usize, value: Spec::T) -> Option<()> {
+        if index >= self.size {
+            return None;
+        }
+        self.update(index, value);
+        Some(())
+    }
+
+    /// Applies `f` to every element in the range, then rebuilds the affected
+    /// internal nodes once.
+    ///
+    /// A lighter alternative to [`LazySegTree`](crate::LazySegTree) when
+    /// range updates are infrequent. Changes are journaled like
+    /// [`update`](Self::update) while checkpoints are active.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// tree.apply_range(1..4, |x| *x *= 10);
+    /// assert_eq!(tree.query(..), 96);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(k + log n) calls to `op`, where k is the length of the range.
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn apply_range<R, F>(&mut self, range: R, f: F)
+    where
+        R: RangeBounds<usize>,
+        F: Fn(&mut Spec::T),
+    {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        if left == right {
+            return;
+        }
+
+        for leaf_index in left + self.max_size..right + self.max_size {
+            if self.journal.active {
+                let old = self.data[leaf_index].clone();
+                self.journal.entries.push((leaf_index, old));
+            }
+            f(&mut self.data[leaf_index]);
+        }
+        self.rebuild_range(left, right);
+    }
+
+    /// Sets every element `a[i]` in the range to `op(a[i], v)`, then rebuilds
+    /// the affected internal nodes once.
+    ///
+    /// For example, on a max tree this raises every element to at least `v`.
+    /// Useful when `v` varies between calls or range updates are rare enough
+    /// that a [`LazySegTree`](crate::LazySegTree) is not worth it. Changes
+    /// are journaled like [`update`](Self::update) while checkpoints are
+    /// active.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::helpers::SegTreeMax;
+    ///
+    /// let mut tree = SegTreeMax::<i32>::from_vec(vec![1, 7, 3, 9, 2]);
+    /// tree.combine_range(1..4, 5);
+    /// assert_eq!(tree.query(1..3), 7); // max(7, 5), max(3, 5)
+    /// assert_eq!(tree.query(2..3), 5);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(k + log n) calls to `op`, where k is the length of the range.
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn combine_range<R: RangeBounds<usize>>(&mut self, range: R, v: Spec::T) {
+        self.apply_range(range, |leaf| Spec::op(leaf, &v));
+    }
+
+    /// Calls `f` with the index and a mutable reference to every element,
+    /// then rebuilds the internal nodes once.
+    ///
+    /// Cheaper than calling [`update`](Self::update) per element, which
+    /// costs O(n log n) for the whole array. Changes are journaled like
+    /// [`update`](Self::update) while checkpoints are active.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.modify_all(|i, x| *x += i as i32);
+    /// assert_eq!(tree.query(..), 16);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn modify_all<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, &mut Spec::T),
+    {
+        for i in 0..self.size {
+            let leaf_index = self.max_size + i;
+            if self.journal.active {
+                let old = self.data[leaf_index].clone();
+                self.journal.entries.push((leaf_index, old));
+            }
+            f(i, &mut self.data[leaf_index]);
+        }
+        self.rebuild();
+    }
+
+    /// Applies `f` to the `len` elements starting at `start`, like
+    /// [`apply_range`](Self::apply_range) over `start..start + len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// tree.apply_len(3, 2, |x| *x = 0);
+    /// assert_eq!(tree.query(..), 6);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(len + log n) calls to `op`
+    ///
+    /// # Panics
+    /// Panics if `start + len` overflows or exceeds the tree size.
+    pub fn apply_len<F>(&mut self, start: usize, len: usize, f: F)
+    where
+        F: Fn(&mut Spec::T),
+    {
+        let (left, right) = utils::parse_len_range(start, len, self.size);
+        self.apply_range(left..right, f);
+    }
+
+    /// Marks the current state so later point updates can be undone with
+    /// [`rollback`](Self::rollback).
+    ///
+    /// The first call turns on journaling: from then on every
+    /// [`update`](Self::update), [`replace`](Self::replace),
+    /// [`swap`](Self::swap), [`apply_range`](Self::apply_range) and
+    /// [`modify_all`](Self::modify_all) records the values it overwrites.
+    /// Structural changes (`push`, `pop`, `resize`, `extend`, `clear`, `fill`,
+    /// `reset_from_slice`, `grow_in_place`, and `reserve`/`shrink_to_fit` when
+    /// they reallocate) discard the journal and invalidate all checkpoints. Use
+    /// [`discard_checkpoints`](Self::discard_checkpoints) to stop journaling.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// let cp = tree.checkpoint();
+    /// tree.update(0, 10);
+    /// tree.update(2, 30);
+    /// assert_eq!(tree.query(..), 42);
+    /// tree.rollback(cp);
+    /// assert_eq!(tree.query(..), 6);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(1)
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        self.journal.active = true;
+        Checkpoint {
+            epoch: self.journal.epoch,
+            len: self.journal.entries.len(),
+        }
+    }
+
+    /// Undoes every point update made since `checkpoint` was taken.
+    ///
+    /// Checkpoints taken after `checkpoint` become invalid; earlier ones stay
+    /// usable.
+    ///
+    /// # Time Complexity
+    /// O(k log n) for `k` undone updates
+    ///
+    /// # Panics
+    /// Panics if the checkpoint was invalidated by a structural change, by
+    /// [`discard_checkpoints`](Self::discard_checkpoints), or by rolling back
+    /// past it.
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        assert!(
+            checkpoint.epoch == self.journal.epoch && checkpoint.len <= self.journal.entries.len(),
+            "Checkpoint is no longer valid"
+        );
+        while self.journal.entries.len() > checkpoint.len {
+            if let Some((leaf_index, old)) = self.journal.entries.pop() {
+                self.data[leaf_index] = old;
+                self.recompute(leaf_index);
+            }
+        }
+    }
+
+    /// Stops journaling, frees the undo log and invalidates all checkpoints.
+    ///
+    /// # Time Complexity
+    /// O(k) for `k` logged updates
+    pub fn discard_checkpoints(&mut self) {
+        self.journal.reset();
+        self.journal.entries.shrink_to_fit();
+    }
+
+    /// Captures the current elements so later changes can be listed with
+    /// [`changed_since`](Self::changed_since).
+    ///
+    /// Unlike [`checkpoint`](Self::checkpoint), a snapshot does not enable
+    /// journaling and survives structural changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4]);
+    /// let snap = tree.snapshot();
+    /// tree.update(1, 20);
+    /// tree.update(3, 4); // same value, not a change
+    /// assert_eq!(tree.changed_since(&snap), vec![1]);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn snapshot(&self) -> Snapshot<Spec::T> {
+        Snapshot {
+            values: self.leaves().to_vec(),
+        }
+    }
+
+    /// Returns the indices whose value differs from `snapshot`, in
+    /// increasing order.
+    ///
+    /// If the length changed in between, every index present in only one of
+    /// the two is reported as well, so indices may reach up to the longer of
+    /// the two lengths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// let snap = tree.snapshot();
+    /// tree.push(4);
+    /// tree.update(0, 0);
+    /// assert_eq!(tree.changed_since(&snap), vec![0, 3]);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn changed_since(&self, snapshot: &Snapshot<Spec::T>) -> Vec<usize>
+    where
+        Spec::T: PartialEq,
+    {
+        let current = self.leaves();
+        let common = current.len().min(snapshot.values.len());
+        let longest = current.len().max(snapshot.values.len());
+        (0..common)
+            .filter(|&i| current[i] != snapshot.values[i])
+            .chain(common..longest)
+            .collect()
+    }
+
+    /// Reloads the tree with `values`, reusing the current allocation when
+    /// they fit in the current [`capacity`](Self::capacity).
+    ///
+    /// Only reallocates when `values` is longer than the capacity. Discards
+    /// the undo journal like other structural changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.reset_from_slice(&[10, 20]);
+    /// assert_eq!(tree.len(), 2);
+    /// assert_eq!(tree.query(..), 30);
+    /// assert_eq!(tree.capacity(), 4);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(max(n, capacity))
+    pub fn reset_from_slice(&mut self, values: &[Spec::T]) {
+        #[cfg(debug_assertions)]
+        Spec::debug_check(values);
+
+        self.journal.reset();
+        if values.len() > self.max_size {
+            self.max_size = values.len().next_power_of_two();
+            self.data = vec![Spec::id(); 2 * self.max_size].into_boxed_slice();
+        } else {
+            // Reset leaves past the new end so padding stays neutral
+            for i in values.len()..self.size {
+                self.data[self.max_size + i] = Spec::id();
+            }
+        }
+
+        self.data[self.max_size..self.max_size + values.len()].clone_from_slice(values);
+        self.size = values.len();
+        self.rebuild();
+    }
+
+    /// Ensures capacity for at least `len + additional` elements, so that
+    /// many [`push`](Self::push) calls do not reallocate.
+    ///
+    /// The capacity is rounded up to a power of two. Does nothing if the
+    /// current capacity already suffices; otherwise the undo journal is
+    /// discarded like other structural changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// tree.reserve(10);
+    /// assert_eq!(tree.capacity(), 16);
+    /// assert_eq!(tree.query(..), 6);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(new capacity) when reallocating, O(1) otherwise
+    ///
+    /// # Panics
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self
+            .size
+            .checked_add(additional)
+            .and_then(usize::checked_next_power_of_two)
+            .expect("capacity overflow");
+        if required > self.max_size {
+            self.journal.reset();
+            self.relayout(required);
+            self.rebuild();
+        }
+    }
+
+    /// Shrinks the capacity to the smallest power of two holding `len`
+    /// elements.
+    ///
+    /// Does nothing if the capacity is already tight; otherwise the undo
+    /// journal is discarded like other structural changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::with_capacity(100);
+    /// tree.extend([1, 2, 3]);
+    /// tree.shrink_to_fit();
+    /// assert_eq!(tree.capacity(), 4);
+    /// assert_eq!(tree.query(..), 6);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n) when reallocating, O(1) otherwise
+    pub fn shrink_to_fit(&mut self) {
+        let tight = self.size.next_power_of_two();
+        if tight < self.max_size {
+            self.journal.reset();
+            self.relayout(tight);
+            self.rebuild();
+        }
+    }
+
+    /// Doubles the capacity, keeping every existing node in place.
+    ///
+    /// Growth by exactly one doubling never needs a full rebuild: the old tree
+    /// becomes the left subtree of the new root, so each old node moves to the
+    /// same position one level deeper, the new right subtree is all padding,
+    /// and only the new root is recomputed. [`push`](Self::push) takes this
+    /// path when it runs out of room; growing by more than one doubling, as
+    /// in [`reserve`](Self::reserve), still rebuilds. Discards the undo
+    /// journal like other structural changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.grow_in_place();
+    /// assert_eq!(tree.capacity(), 8);
+    /// assert_eq!(tree.len(), 4);
+    /// assert_eq!(tree.query(1..), 9);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n) moves and a single `op`
+    ///
+    /// # Panics
+    /// Panics if the new capacity overflows `usize`.
+    pub fn grow_in_place(&mut self) {
+        self.journal.reset();
+        let new_max_size = self.max_size.checked_mul(2).expect("capacity overflow");
+
+        let mut old = core::mem::take(&mut self.data).into_vec().into_iter();
+        let mut data = Vec::with_capacity(2 * new_max_size);
+        // Slot 0 is unused; the root is recomputed below
+        data.push(old.next().unwrap_or_else(Spec::id));
+        data.push(Spec::id());
+
+        // Each new level is the old level above it followed by as much padding
+        let mut width = 1;
+        while width <= self.max_size {
+            data.extend(old.by_ref().take(width));
+            data.resize(data.len() + width, Spec::id());
+            width *= 2;
+        }
+
+        let mut root = data[2].clone();
+        Spec::op(&mut root, &data[3]);
+        data[1] = root;
+        self.data = data.into_boxed_slice();
+        self.max_size = new_max_size;
+
+        #[cfg(debug_assertions)]
+        self.debug_check_padding();
+    }
+
+    /// Resizes the tree to `new_size` elements, preserving existing values.
+    ///
+    /// New trailing elements are set to `fill`; when shrinking, trailing
+    /// elements are dropped. The internal layout is reallocated only when the
+    /// next power of two of `new_size` differs from the current one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// tree.resize(5, 10);
+    /// assert_eq!(tree.query(..), 26);
+    /// tree.resize(2, 0);
+    /// assert_eq!(tree.query(..), 3);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n) where n is `max(size, new_size)`
+    pub fn resize(&mut self, new_size: usize, fill: Spec::T) {
+        #[cfg(debug_assertions)]
+        Spec::debug_check(core::slice::from_ref(&fill));
+
+        self.journal.reset();
+        // Reset dropped leaves so padding stays neutral
+        for i in new_size..self.size {
+            self.data[self.max_size + i] = Spec::id();
+        }
+        self.size = self.size.min(new_size);
+
+        let new_max_size = new_size.next_power_of_two();
+        if new_max_size != self.max_size {
+            self.relayout(new_max_size);
+        }
+
+        for i in self.size..new_size {
+            self.data[self.max_size + i] = fill.clone();
+        }
+        self.size = new_size;
+        self.rebuild();
+    }
+
+    /// Appends a value to the end of the tree.
+    ///
+    /// When the internal capacity is exhausted, it is doubled, so a sequence
+    /// of pushes costs amortized O(log n) each.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2]);
+    /// tree.push(3);
+    /// assert_eq!(tree.query(..), 6);
+    /// assert_eq!(tree.query(2..), 3);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// Amortized O(log n)
+    pub fn push(&mut self, value: Spec::T) {
+        #[cfg(debug_assertions)]
+        Spec::debug_check(core::slice::from_ref(&value));
+
+        self.journal.reset();
+        if self.size == self.max_size {
+            self.grow_in_place();
+        }
+
+        let leaf_index = self.max_size + self.size;
+        self.data[leaf_index] = value;
+        self.size += 1;
+        self.recompute(leaf_index);
+    }
+
+    /// Removes the last value and returns it, or `None` if the tree is empty.
+    ///
+    /// The internal capacity is kept as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// assert_eq!(tree.pop(), Some(3));
+    /// assert_eq!(tree.query(..), 3);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    pub fn pop(&mut self) -> Option<Spec::T> {
+        if self.size == 0 {
+            return None;
+        }
+        self.journal.reset();
+
+        self.size -= 1;
+        let leaf_index = self.max_size + self.size;
+        let value = core::mem::replace(&mut self.data[leaf_index], Spec::id());
+        self.recompute(leaf_index);
+
+        #[cfg(debug_assertions)]
+        self.debug_check_padding();
+
+        Some(value)
+    }
+
+    /// Builds a tree under another spec by applying `f` to every element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::helpers::SegTreeSumSpec;
+    /// use array_range_query::{SegTree, SegTreeSum};
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// let squares: SegTree<SegTreeSumSpec<i64>> = tree.map(|&x| (x as i64) * (x as i64));
+    /// assert_eq!(squares.query(..), 14);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn map<S2, F>(&self, f: F) -> SegTree<S2>
+    where
+        S2: SegTreeSpec,
+        F: Fn(&Spec::T) -> S2::T,
+    {
+        SegTree::from_vec(self.leaves().iter().map(f).collect())
+    }
+
+    /// Returns a tree over the elements of `self` followed by those of `other`.
+    ///
+    /// The storage of `self` is reused when it has room for both; otherwise
+    /// it grows once. Internal nodes are rebuilt in a single bottom-up pass.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let left = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// let right = SegTreeSum::<i32>::from_vec(vec![4, 5]);
+    /// let tree = left.concat(right);
+    /// assert_eq!(tree.query(..), 15);
+    /// assert_eq!(tree.query(2..4), 7);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n + m)
+    pub fn concat(mut self, other: Self) -> Self {
+        self.extend(other.into_leaves());
+        self
+    }
+
+    /// Splits the tree into trees over `[0, at)` and `[at, len)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+    /// let (left, right) = tree.split(2);
+    /// assert_eq!(left.query(..), 3);
+    /// assert_eq!(right.query(..), 12);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    ///
+    /// # Panics
+    /// Panics if `at > len`.
+    pub fn split(self, at: usize) -> (Self, Self) {
+        assert!(at <= self.size, "split index out of bounds");
+        let mut left = self.into_leaves();
+        let right = left.split_off(at);
+        (Self::from_vec(left), Self::from_vec(right))
+    }
+
+    /// Resets every element to `Spec::id()`, keeping the length and the
+    /// allocated storage.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3]);
+    /// tree.clear();
+    /// assert_eq!(tree.len(), 3);
+    /// assert_eq!(tree.query(..), 0);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn clear(&mut self) {
+        self.journal.reset();
+        self.data.fill(Spec::id());
+    }
+
+    /// Sets every element to `value` and rebuilds the internal nodes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeSum;
+    ///
+    /// let mut tree = SegTreeSum::<i32>::new(5);
+    /// tree.fill(3);
+    /// assert_eq!(tree.query(..), 15);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn fill(&mut self, value: Spec::T) {
+        #[cfg(debug_assertions)]
+        Spec::debug_check(core::slice::from_ref(&value));
+
+        self.journal.reset();
+        self.data[self.max_size..self.max_size + self.size].fill(value);
+        self.rebuild();
+    }
+
+    // ===== PRIVATE HELPER METHODS =====
+
+    /// Returns the logical leaves in order.
+    pub(crate) fn leaves(&self) -> &[Spec::T] {
+        &self.data[self.max_size..self.max_size + self.size]
+    }
+
+    /// Returns the index and value of the first element in the range for
+    /// which no other element is `better`, or `None` if the range is empty.
+    ///
+    /// `better(a, b)` must be a strict order consistent with `op`, i.e. the
+    /// aggregate of a node is its best leaf.
+    pub(crate) fn arg_best<R, F>(&self, range: R, better: F) -> Option<(usize, Spec::T)>
+    where
+        R: RangeBounds<usize>,
+        F: Fn(&Spec::T, &Spec::T) -> bool,
+    {
+        // Pick the leftmost best canonical node, then descend into the
+        // leftmost child whose aggregate still equals the best value.
+        let mut best: Option<(SegTreeNode, &Spec::T)> = None;
+        for (node, value) in self.query_segments(range) {
+            if best.is_none_or(|(_, b)| better(value, b)) {
+                best = Some((node, value));
+            }
+        }
+        let (mut node, value) = best?;
+        while node.0 < self.max_size {
+            let left = node.left_child();
+            node = if better(value, &self.data[left.0]) {
+                node.right_child()
+            } else {
+                left
+            };
+        }
+        Some((node.0 - self.max_size, value.clone()))
+    }
+
+    /// Returns the first index in the range whose leaf satisfies `pred`, or
+    /// `None` if there is none.
+    ///
+    /// `pred` must hold for a node's aggregate exactly when it holds for at
+    /// least one leaf below it (e.g. `max >= threshold`), so whole subtrees
+    /// can be pruned.
+    pub(crate) fn find_first<R, F>(&self, range: R, pred: F) -> Option<usize>
+    where
+        R: RangeBounds<usize>,
+        F: Fn(&Spec::T) -> bool,
+    {
+        let (mut node, _) = self.query_segments(range).find(|(_, v)| pred(v))?;
+        while node.0 < self.max_size {
+            let left = node.left_child();
+            node = if pred(&self.data[left.0]) {
+                left
+            } else {
+                node.right_child()
+            };
+        }
+        Some(node.0 - self.max_size)
+    }
+
+    /// Consumes the tree, returning the logical leaves in order.
+    fn into_leaves(self) -> Vec<Spec::T> {
+        let mut data = self.data.into_vec();
+        data.truncate(self.max_size + self.size);
+        data.drain(..self.max_size);
+        data
+    }
+
+    /// Folds the validated range `[left, right)`.
+    fn query_in(&self, left: usize, right: usize) -> Spec::T {
+        if left == right {
+            return Spec::id();
+        }
+
+        // Map the logical range to the internal array indices
+        let mut left = left + self.max_size;
+        let mut right = right + self.max_size;
+
+        // Accumulators for the left and right sides of the range. `None` stands
+        // for the identity, so the first node on each side is cloned instead of
+        // combined with `ID`; a single-node range costs one clone and no `op`.
+        let mut result_left: Option<Spec::T> = None;
+        let mut result_right: Option<Spec::T> = None;
+
+        // Standard segment tree range query algorithm
+        while left < right {
+            // If left is odd (right child), include it and move to next
+            if left & 1 == 1 {
+                match result_left.as_mut() {
+                    Some(acc) => Spec::op(acc, &self.data[left]),
+                    None => result_left = Some(self.data[left].clone()),
+                }
+                left += 1;
+            }
+            // If right is odd (right child), include the left sibling and move back.
+            // It lies left of everything gathered so far, so it goes in front.
+            if right % 2 == 1 {
+                right -= 1;
+                let v = self.data[right].clone();
+                result_right = Some(match result_right.take() {
+                    Some(acc) => Spec::op_owned(v, acc),
+                    None => v,
+                });
+            }
+            // Move up to parent level
+            left /= 2;
+            right /= 2;
+        }
+
+        // Combine the left and right results
+        match (result_left, result_right) {
+            (Some(l), Some(r)) => Spec::op_owned(l, r),
+            (Some(v), None) | (None, Some(v)) => v,
+            (None, None) => Spec::id(),
+        }
+    }
+
+    /// Moves the logical leaves into a fresh layout with `new_max_size` leaves.
+    ///
+    /// Internal nodes are left as `Spec::ID`; callers must rebuild afterwards.
+    fn relayout(&mut self, new_max_size: usize) {
+        let old = core::mem::take(&mut self.data).into_vec();
+        let mut data = vec![Spec::id(); 2 * new_max_size];
+        for (i, v) in old
+            .into_iter()
+            .skip(self.max_size)
+            .take(self.size)
+            .enumerate()
+        {
+            data[new_max_size + i] = v;
+        }
+        self.data = data.into_boxed_slice();
+        self.max_size = new_max_size;
+    }
+
+    /// Rebuilds every internal node from the leaves.
+    fn rebuild(&mut self) {
+        for i in (1..self.max_size).rev() {
+            let mut v = self.data[i * 2].clone();
+            Spec::op(&mut v, &self.data[i * 2 + 1]);
+            self.data[i] = v;
+        }
+
+        #[cfg(debug_assertions)]
+        self.debug_check_padding();
+    }
+
+    /// Passes the padding leaves and the top-most padding nodes to
+    /// [`SegTreeSpec::debug_check`].
+    ///
+    /// This only checks anything for specs that opt into
+    /// [`check_monoid_laws`](SegTreeSpec::check_monoid_laws), which then
+    /// verify `op(p, ID) == p`, `op(ID, p) == p` and `op(ID, ID) == ID`,
+    /// catching an `op` that turns identity inputs into something else. The
+    /// exact `padding == ID` check needs `T: PartialEq`, so it lives in the
+    /// test-only `assert_padding_is_id`.
+    #[cfg(debug_assertions)]
+    fn debug_check_padding(&self) {
+        if self.size == self.max_size {
+            return;
+        }
+        Spec::debug_check(core::slice::from_ref(&self.data[self.max_size + self.size]));
+
+        let mut l = self.max_size + self.size;
+        let mut r = 2 * self.max_size;
+        while l < r {
+            if l & 1 == 1 {
+                Spec::debug_check(core::slice::from_ref(&self.data[l]));
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                Spec::debug_check(core::slice::from_ref(&self.data[r]));
+            }
+            l /= 2;
+            r /= 2;
+        }
+    }
+
+    /// Recomputes the ancestors of the non-empty leaf range `[left, right)`
+    /// one level at a time, visiting each affected node once.
+    fn rebuild_range(&mut self, left: usize, right: usize) {
+        let mut lo = (left + self.max_size) / 2;
+        let mut hi = (right - 1 + self.max_size) / 2;
+        while lo >= 1 {
+            for i in lo..=hi {
+                let mut v = self.data[i * 2].clone();
+                Spec::op(&mut v, &self.data[i * 2 + 1]);
+                self.data[i] = v;
+            }
+            lo /= 2;
+            hi /= 2;
+        }
+    }
+
+    /// Recomputes the internal node `index` from its two children.
+    fn pull(&mut self, index: usize) {
+        let mut v = self.data[index * 2].clone();
+        Spec::op(&mut v, &self.data[index * 2 + 1]);
+        self.data[index] = v;
+    }
+
+    /// Recomputes parent nodes from a leaf up to the root.
+    fn recompute(&mut self, mut index: usize) {
+        // Move up the tree level by level
+        while index > 1 {
+            index /= 2; // Move to parent
+
+            // Recompute parent value from its two children
+            let mut v = self.data[index * 2].clone();
+            Spec::op(&mut v, &self.data[index * 2 + 1]);
+            self.data[index] = v;
+        }
+    }
+}
+
+#[cfg(test)]
+impl<Spec: SegTreeSpec> SegTree<Spec>
+where
+    Spec::T: PartialEq + core::fmt::Debug,
+{
+    /// Asserts that every padding leaf and every internal node covering only
+    /// padding equals `Spec::id()`.
+    ///
+    /// The generic tree code cannot call this since `T` need not be
+    /// `PartialEq`, so the tests run it after each structural operation.
+    fn assert_padding_is_id(&self) {
+        let id = Spec::id();
+        let mut lo = self.max_size + self.size;
+        let mut hi = 2 * self.max_size;
+        while lo < hi {
+            for node in lo..hi {
+                assert!(
+                    self.data[node] == id,
+                    "padding node {} is {:?}, expected ID {:?}",
+                    node,
+                    self.data[node],
+                    id
+                );
+            }
+            // Parents of the pure-padding nodes that have no logical leaf below
+            lo = lo.div_ceil(2);
+            hi /= 2;
+        }
+    }
+}
+
+// ===== TRAIT IMPLEMENTATIONS =====
+
+impl<Spec: SegTreeSpec> Clone for SegTree<Spec> {
+    fn clone(&self) -> Self {
+        Self {
+            size: self.size,
+            max_size: self.max_size,
+            data: self.data.clone(),
+            journal: self.journal.clone(),
+            _spec: PhantomData,
+        }
+    }
+}
+
+/// Appends all items, growing the capacity at most once and recomputing
+/// each affected internal node a single time.
+///
+/// # Example
+///
+/// ```
+/// use array_range_query::SegTreeSum;
+///
+/// let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2]);
+/// tree.extend([3, 4, 5]);
+/// assert_eq!(tree.len(), 5);
+/// assert_eq!(tree.query(..), 15);
+/// ```
+impl<Spec: SegTreeSpec> Extend<Spec::T> for SegTree<Spec> {
+    fn extend<I: IntoIterator<Item = Spec::T>>(&mut self, iter: I) {
+        let items: Vec<Spec::T> = iter.into_iter().collect();
+        if items.is_empty() {
+            return;
+        }
+        self.journal.reset();
+
+        let old_size = self.size;
+        let new_size = old_size + items.len();
+        let grown = new_size > self.max_size;
+        if grown {
+            self.relayout(new_size.next_power_of_two());
+        }
+
+        let start = self.max_size + old_size;
+        for (slot, item) in self.data[start..].iter_mut().zip(items) {
+            *slot = item;
+        }
+        self.size = new_size;
+
+        if grown {
+            self.rebuild();
+        } else {
+            self.rebuild_range(old_size, new_size);
+        }
+    }
+}
+
+/// The default tree is empty.
+impl<Spec: SegTreeSpec> Default for SegTree<Spec> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Two trees are equal when they hold the same logical elements.
+///
+/// Capacity and padding are ignored, so trees built in different ways
+/// compare equal as long as their contents match.
+impl<Spec: SegTreeSpec> PartialEq for SegTree<Spec>
+where
+    Spec::T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size
+            && self.data[self.max_size..self.max_size + self.size]
+                == other.data[other.max_size..other.max_size + other.size]
+    }
+}
+
+impl<Spec: SegTreeSpec> Eq for SegTree<Spec> where Spec::T: Eq {}
+
+/// Hashes the logical elements, prefixed by their count, consistently with
+/// [`PartialEq`]: capacity and padding are ignored, so equal trees hash
+/// equally and can be used as `HashMap` keys.
+///
+/// # Example
+///
+/// ```
+/// use array_range_query::SegTreeSum;
+/// use std::collections::HashSet;
+///
+/// let mut seen = HashSet::new();
+/// seen.insert(SegTreeSum::<i32>::from_vec(vec![1, 2, 3]));
+/// let mut pushed = SegTreeSum::<i32>::with_capacity(16);
+/// pushed.extend([1, 2, 3]);
+/// assert!(seen.contains(&pushed));
+/// ```
+impl<Spec: SegTreeSpec> core::hash::Hash for SegTree<Spec>
+where
+    Spec::T: core::hash::Hash,
+{
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.leaves().hash(state);
+    }
+}
+
+impl<Spec: SegTreeSpec> core::fmt::Debug for SegTree<Spec>
+where
+    Spec::T: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SegTree")
+            .field("size", &self.size)
+            .field("max_size", &self.max_size)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Spec: SegTreeSpec> core::fmt::Display for SegTree<Spec>
+where
+    Spec::T: core::fmt::Display + PartialEq,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "SegTree {{")?;
+        writeln!(f, "  Data Type: {}", core::any::type_name::<Spec::T>())?;
+        writeln!(f, "  Size: {} (Internal: {})", self.size, self.max_size)?;
+
+        // Identity-valued nodes are skipped to keep the output readable
+        let identity = Spec::id();
+        let data_values: Vec<Option<Spec::T>> = self
+            .data
+            .iter()
+            .map(|x| (*x != identity).then(|| x.clone()))
+            .collect();
+        let data_values_slice = data_values.iter().collect::<Vec<_>>();
+
+        writeln!(f, "  Data:")?;
+        utils::print_tree_option(f, &data_values_slice, 1, 2, 0, self.max_size)?;
+
+        writeln!(f, "}}")
+    }
+}
+
+// ===== TESTS =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test specification for sum operations.
+    struct SumSpec;
+    impl SegTreeSpec for SumSpec {
+        type T = i64;
+        const ID: Self::T = 0;
+
+        fn op(a: &mut Self::T, b: &Self::T) {
+            *a += *b;
+        }
+    }
+
+    /// Test specification whose `op` is subtraction, which is not associative.
+    struct SubSpec;
+    impl SegTreeSpec for SubSpec {
+        type T = i64;
+        const ID: Self::T = 0;
+
+        fn op(a: &mut Self::T, b: &Self::T) {
+            *a -= *b;
+        }
+
+        fn debug_check(values: &[Self::T]) {
+            Self::check_monoid_laws(values);
+        }
+    }
+
+    #[test]
+    fn test_check_monoid_laws_accepts_valid_spec() {
+        SumSpec::check_monoid_laws(&[1, -2, 3, 40, 0]);
+        SumSpec::check_monoid_laws(&[]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "SegTreeSpec")]
+    fn test_debug_check_rejects_non_associative_spec() {
+        SegTree::<SubSpec>::from_vec(vec![5, 3, 2, 7]);
+    }
+
+    /// Sum specification whose `op` maps two identities to `1`, which would
+    /// corrupt padding nodes built from identity leaves.
+    struct PaddingCorruptingSpec;
+    impl SegTreeSpec for PaddingCorruptingSpec {
+        type T = i64;
+        const ID: Self::T = 0;
+
+        fn op(a: &mut Self::T, b: &Self::T) {
+            if *a == 0 && *b == 0 {
+                *a = 1;
+            } else {
+                *a += *b;
+            }
+        }
+
+        fn debug_check(values: &[Self::T]) {
+            Self::check_monoid_laws(values);
+        }
+    }
+
+    #[test]
+    fn test_padding_corrupting_spec_passes_value_checks() {
+        // The stored values alone satisfy the laws; only padding exposes the bug
+        PaddingCorruptingSpec::check_monoid_laws(&[1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "SegTreeSpec identity violated")]
+    fn test_debug_check_rejects_padding_corruption() {
+        SegTree::<PaddingCorruptingSpec>::from_vec(vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "padding node 14 is 5, expected ID 0")]
+    fn test_assert_padding_is_id_rejects_corrupted_leaf() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5]);
+        seg_tree.assert_padding_is_id();
+        seg_tree.data[14] = 5;
+        seg_tree.assert_padding_is_id();
+    }
+
+    #[test]
+    #[should_panic(expected = "padding node 7 is 5, expected ID 0")]
+    fn test_assert_padding_is_id_rejects_corrupted_internal_node() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5]);
+        // Node 6 covers element 4 and a padding leaf, so it is not checked;
+        // node 7 covers only padding
+        seg_tree.data[6] = 7;
+        seg_tree.assert_padding_is_id();
+        seg_tree.data[7] = 5;
+        seg_tree.assert_padding_is_id();
+    }
+
+    #[test]
+    fn test_padding_stays_id_across_structural_operations() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5]);
+        seg_tree.assert_padding_is_id();
+
+        seg_tree.push(6);
+        seg_tree.assert_padding_is_id();
+        for v in 7..=11 {
+            seg_tree.push(v);
+            seg_tree.assert_padding_is_id();
+        }
+        for _ in 0..4 {
+            seg_tree.pop();
+            seg_tree.assert_padding_is_id();
+        }
+        seg_tree.resize(13, 1);
+        seg_tree.assert_padding_is_id();
+        seg_tree.resize(3, 1);
+        seg_tree.assert_padding_is_id();
+        seg_tree.grow_in_place();
+        seg_tree.assert_padding_is_id();
+        seg_tree.extend([8, 9]);
+        seg_tree.assert_padding_is_id();
+        seg_tree.apply_range(.., |v| *v *= 2);
+        seg_tree.assert_padding_is_id();
+        seg_tree.shrink_to_fit();
+        seg_tree.assert_padding_is_id();
+        seg_tree.fill(4);
+        seg_tree.assert_padding_is_id();
+        seg_tree.reset_from_slice(&[1, 2, 3]);
+        seg_tree.assert_padding_is_id();
+        seg_tree.clear();
+        seg_tree.assert_padding_is_id();
+        assert_eq!(seg_tree.query(..), 0);
+    }
+
+    /// Sum specification with a non-neutral identity, the typical user mistake.
+    struct WrongIdSumSpec;
+    impl SegTreeSpec for WrongIdSumSpec {
+        type T = i64;
+        const ID: Self::T = 1;
+
+        fn op(a: &mut Self::T, b: &Self::T) {
+            *a += *b;
+        }
+
+        fn debug_check(values: &[Self::T]) {
+            Self::check_monoid_laws(values);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(
+        expected = "over 3 values starting with [1, 2, 3], padded with ID = 1, is 7, but their left fold is 6"
+    )]
+    fn test_debug_check_rejects_wrong_id() {
+        SegTree::<WrongIdSumSpec>::from_vec(vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_height_and_mem_bytes() {
+        for size in [0, 1, 2, 5, 8, 9, 1000] {
+            let tree = SegTree::<SumSpec>::new(size);
+            let max_size = size.next_power_of_two();
+            assert_eq!(tree.height(), max_size.trailing_zeros());
+            assert_eq!(tree.mem_bytes(), 2 * max_size * core::mem::size_of::<i64>());
+        }
+    }
+
+    static COUNTED_OPS: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+    /// Sum specification that counts how often `op` is called.
+    struct CountingSumSpec;
+    impl SegTreeSpec for CountingSumSpec {
+        type T = i64;
+        const ID: Self::T = 0;
+
+        fn op(a: &mut Self::T, b: &Self::T) {
+            COUNTED_OPS.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+            *a += *b;
+        }
+    }
+
+    #[test]
+    fn test_query_skips_identity_ops() {
+        use core::sync::atomic::Ordering;
+
+        let values: Vec<i64> = (1..=13).collect();
+        let seg_tree = SegTree::<CountingSumSpec>::from_slice(&values);
+
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                let nodes = seg_tree.canonical_nodes(l..r).len();
+                COUNTED_OPS.store(0, Ordering::Relaxed);
+                assert_eq!(seg_tree.query(l..r), values[l..r].iter().sum::<i64>());
+                // k canonical nodes are combined with exactly k - 1 ops
+                assert_eq!(COUNTED_OPS.load(Ordering::Relaxed), nodes.saturating_sub(1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_if_changed_skips_unchanged_leaf() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static OPS: AtomicUsize = AtomicUsize::new(0);
+
+        /// Sum specification with its own op counter.
+        struct CountingSpec;
+        impl SegTreeSpec for CountingSpec {
+            type T = i64;
+            const ID: Self::T = 0;
+
+            fn op(a: &mut Self::T, b: &Self::T) {
+                OPS.fetch_add(1, Ordering::Relaxed);
+                *a += *b;
+            }
+        }
+
+        let values: Vec<i64> = (1..=13).collect();
+        let mut seg_tree = SegTree::<CountingSpec>::from_slice(&values);
+        let checkpoint = seg_tree.checkpoint();
+
+        OPS.store(0, Ordering::Relaxed);
+        for (i, &v) in values.iter().enumerate() {
+            assert!(!seg_tree.update_if_changed(i, v));
+        }
+        assert_eq!(OPS.load(Ordering::Relaxed), 0);
+        assert_eq!(seg_tree.leaves(), &values[..]);
+        assert_eq!(seg_tree.total(), 91);
+
+        assert!(seg_tree.update_if_changed(4, 50));
+        assert_eq!(OPS.load(Ordering::Relaxed), seg_tree.height() as usize);
+        assert_eq!(seg_tree.query(3..6), 4 + 50 + 6);
+
+        seg_tree.rollback(checkpoint);
+        assert_eq!(seg_tree, SegTree::<CountingSpec>::from_slice(&values));
+    }
+
+    /// Concatenation monoid whose identity is only available through `id()`.
+    struct ConcatSpec;
+    impl SegTreeSpec for ConcatSpec {
+        type T = String;
+        fn id() -> Self::T {
+            String::new()
+        }
+        fn op(a: &mut Self::T, b: &Self::T) {
+            a.push_str(b);
+        }
+        fn debug_check(values: &[Self::T]) {
+            Self::check_monoid_laws(values);
+        }
+    }
+
+    #[test]
+    fn test_query_rev_string_concat() {
+        let letters: Vec<String> = ["a", "b", "c", "d", "e"].map(String::from).to_vec();
+        let seg_tree = SegTree::<ConcatSpec>::from_slice(&letters);
+
+        assert_eq!(seg_tree.query(..3), "abc");
+        assert_eq!(seg_tree.query_rev(..3), "cba");
+        for l in 0..=letters.len() {
+            for r in l..=letters.len() {
+                let reversed: String = letters[l..r].iter().rev().map(String::as_str).collect();
+                assert_eq!(seg_tree.query_rev(l..r), reversed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_query_rev_commutative_equals_query() {
+        let seg_tree = SegTree::<SumSpec>::from_vec(vec![4, -1, 7, 0, 3, 9]);
+        for l in 0..=6 {
+            for r in l..=6 {
+                assert_eq!(seg_tree.query_rev(l..r), seg_tree.query(l..r));
+            }
+        }
+    }
+
+    #[test]
+    fn test_runtime_identity_string_concat() {
+        let words: Vec<String> = ["a", "bc", "", "def", "g"].map(String::from).to_vec();
+        let mut seg_tree = SegTree::<ConcatSpec>::from_slice(&words);
+
+        for l in 0..=words.len() {
+            for r in l..=words.len() {
+                assert_eq!(seg_tree.query(l..r), words[l..r].concat());
+            }
+        }
+
+        seg_tree.update(2, String::from("XY"));
+        assert_eq!(seg_tree.query(..), "abcXYdefg");
+        seg_tree.push(String::from("!"));
+        assert_eq!(seg_tree.query(3..), "defg!");
+        assert_eq!(SegTree::<ConcatSpec>::new(3).query(..), "");
+    }
+
+    #[test]
+    fn test_new_empty() {
+        let seg_tree = SegTree::<SumSpec>::new(10);
+        assert_eq!(seg_tree.query(..), 0);
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let trees = [
+            SegTree::<SumSpec>::new(0),
+            SegTree::<SumSpec>::from_slice(&[]),
+            SegTree::<SumSpec>::from_vec(Vec::new()),
+        ];
+        for seg_tree in &trees {
+            assert_eq!(seg_tree.len(), 0);
+            assert!(seg_tree.is_empty());
+            assert_eq!(seg_tree.capacity(), 1);
+            assert_eq!(seg_tree.internal_len(), 2);
+            assert_eq!(seg_tree.total(), 0);
+            assert_eq!(seg_tree.query(..), 0);
+            assert_eq!(seg_tree.query(0..0), 0);
+            assert_eq!(seg_tree.try_query(0..1), None);
+            assert_eq!(seg_tree.lower_bound(1), 0);
+            assert!(seg_tree.leaves().is_empty());
+            assert_eq!(seg_tree, &trees[0]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_empty_from_slice_non_empty_range() {
+        SegTree::<SumSpec>::from_slice(&[]).query(0..1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_empty_from_vec_non_empty_range() {
+        SegTree::<SumSpec>::from_vec(Vec::new()).query(0..1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_empty_tree_non_empty_range() {
+        let seg_tree = SegTree::<SumSpec>::new(0);
+        seg_tree.query(..1);
+    }
+
+    #[test]
+    fn test_from_slice_with_query() {
+        let values = vec![1, 2, 3];
+        let seg_tree = SegTree::<SumSpec>::from_slice(&values);
+
+        // Comprehensively test if querying works correctly for any range
+        assert_eq!(seg_tree.query(0..1), 1);
+        assert_eq!(seg_tree.query(1..2), 2);
+        assert_eq!(seg_tree.query(2..3), 3);
+        assert_eq!(seg_tree.query(..2), 3);
+        assert_eq!(seg_tree.query(1..), 5);
+        assert_eq!(seg_tree.query(..), 6);
+    }
+
+    #[test]
+    fn test_from_vec_with_query() {
+        let values = vec![1, 2, 3];
+        let seg_tree = SegTree::<SumSpec>::from_vec(values);
 
         // Comprehensively test if querying works correctly for any range
         assert_eq!(seg_tree.query(0..1), 1);
@@ -322,6 +2873,45 @@ mod tests {
         assert_eq!(seg_tree.query(..), 6);
     }
 
+    #[test]
+    fn test_from_boxed_leaves_matches_from_vec() {
+        for len in [0, 1, 2, 5, 8, 13] {
+            let values: Vec<i64> = (0..len).map(|i| i * 3 - 7).collect();
+            let boxed = SegTree::<SumSpec>::from_boxed_leaves(values.clone().into_boxed_slice());
+            let from_vec = SegTree::<SumSpec>::from_vec(values);
+            assert_eq!(boxed.data, from_vec.data);
+            assert_eq!(boxed.len(), from_vec.len());
+        }
+    }
+
+    #[test]
+    fn test_build_owned_borrowed_and_cow() {
+        let values = vec![1, 2, 3, 4, 5];
+
+        let from_borrowed = SegTree::<SumSpec>::build(&values[..]);
+        let from_cow = SegTree::<SumSpec>::build(Cow::Borrowed(&values[..]));
+        let from_owned = SegTree::<SumSpec>::build(values);
+
+        for tree in [&from_borrowed, &from_cow, &from_owned] {
+            assert_eq!(tree.query(..), 15);
+            assert_eq!(tree.query(1..4), 9);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_from_vec_parallel_matches_serial() {
+        for size in [0, 1, 2, 5, 1000, 4096, 10_007] {
+            let values: Vec<i64> = (0..size as i64).map(|x| x * 7 % 13 - 6).collect();
+            let serial = SegTree::<SumSpec>::from_vec(values.clone());
+            let parallel = SegTree::<SumSpec>::from_vec_parallel(values);
+
+            assert_eq!(serial.size, parallel.size);
+            assert_eq!(serial.max_size, parallel.max_size);
+            assert_eq!(serial.data, parallel.data);
+        }
+    }
+
     #[test]
     fn test_query_sub_ranges() {
         let seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5, 6, 7, 8]);
@@ -334,24 +2924,307 @@ mod tests {
     }
 
     #[test]
-    fn test_query_empty_range() {
-        let seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3]);
+    fn test_query_batch_matches_query() {
+        use rand::Rng;
+
+        let mut rng = rand::rng();
+        let values: Vec<i64> = (0..50).map(|_| rng.random_range(-100..100)).collect();
+        let seg_tree = SegTree::<SumSpec>::from_slice(&values);
+
+        let ranges: Vec<_> = (0..100)
+            .map(|_| {
+                let l = rng.random_range(0..=values.len());
+                let r = rng.random_range(l..=values.len());
+                l..r
+            })
+            .collect();
+        let expected: Vec<i64> = ranges.iter().map(|r| seg_tree.query(r.clone())).collect();
+        assert_eq!(seg_tree.query_batch(&ranges), expected);
+        assert!(seg_tree.query_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_query_len_and_apply_len() {
+        let values: Vec<i64> = (1..=9).collect();
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&values);
+
+        for start in 0..=values.len() {
+            for len in 0..=values.len() - start {
+                assert_eq!(
+                    seg_tree.query_len(start, len),
+                    seg_tree.query(start..start + len)
+                );
+            }
+        }
+        assert_eq!(seg_tree.query_len(9, 0), 0);
+
+        seg_tree.apply_len(2, 3, |x| *x *= 2);
+        assert_eq!(seg_tree.query(..), 45 + 3 + 4 + 5);
+        seg_tree.apply_len(0, 0, |x| *x = 100);
+        assert_eq!(seg_tree.query(..), 57);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows usize")]
+    fn test_panic_query_len_overflow() {
+        let seg_tree = SegTree::<SumSpec>::from_slice(&[1, 2, 3]);
+        seg_tree.query_len(2, usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_query_len_past_end() {
+        let seg_tree = SegTree::<SumSpec>::from_slice(&[1, 2, 3]);
+        seg_tree.query_len(2, 2);
+    }
+
+    #[test]
+    fn test_query_empty_range() {
+        let seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3]);
+
+        assert_eq!(seg_tree.query(1..1), 0);
+        assert_eq!(seg_tree.query(3..3), 0);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_query_range_inclusive_checked() {
+        let seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+        assert_eq!(seg_tree.query_range_inclusive_checked(0..=7), 36);
+        assert_eq!(seg_tree.query_range_inclusive_checked(4..=4), 5);
+
+        // The unchecked query treats a reversed inclusive range as empty
+        assert_eq!(seg_tree.query(5..=4), 0);
+        assert_eq!(seg_tree.query(5..5), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "reversed inclusive range [5, 4]")]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_panic_query_range_inclusive_checked_reversed() {
+        let seg_tree = SegTree::<SumSpec>::new(10);
+        seg_tree.query_range_inclusive_checked(5..=4);
+    }
+
+    #[test]
+    fn test_canonical_nodes_tile_range() {
+        let size = 13;
+        let seg_tree = SegTree::<SumSpec>::from_vec((1..=size as i64).collect());
+        let max_depth = seg_tree.max_size.trailing_zeros();
+
+        for l in 0..=size {
+            for r in l..=size {
+                let nodes = seg_tree.canonical_nodes(l..r);
+                let mut cursor = l;
+                let mut sum = 0;
+                for node in &nodes {
+                    let (node_left, node_right) = node.node_bounds(max_depth);
+                    assert_eq!(node_left, cursor, "gap or overlap in {}..{}", l, r);
+                    cursor = node_right;
+                    sum += seg_tree.data[node.0];
+                }
+                assert_eq!(cursor, r);
+                assert_eq!(sum, seg_tree.query(l..r));
+                assert!(nodes.len() <= 2 * max_depth as usize + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_query_segments_fold() {
+        let values: Vec<i64> = (1..=13).map(|x| x * x).collect();
+        let seg_tree = SegTree::<SumSpec>::from_slice(&values);
+
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                let folded: i64 = seg_tree.query_segments(l..r).map(|(_, v)| *v).sum();
+                assert_eq!(folded, values[l..r].iter().sum::<i64>());
+
+                let nodes: Vec<SegTreeNode> =
+                    seg_tree.query_segments(l..r).map(|(n, _)| n).collect();
+                assert_eq!(nodes, seg_tree.canonical_nodes(l..r));
+            }
+        }
+
+        // Order is left to right, which matters for non-commutative ops
+        let words: Vec<String> = ["a", "b", "c", "d", "e"].map(String::from).to_vec();
+        let concat_tree = SegTree::<ConcatSpec>::from_slice(&words);
+        let joined: String = concat_tree
+            .query_segments(1..5)
+            .map(|(_, v)| v.as_str())
+            .collect();
+        assert_eq!(joined, "bcde");
+    }
+
+    #[test]
+    fn test_query_with_matches_query() {
+        let values: Vec<i64> = (1..=13).map(|x| x * 7 % 5).collect();
+        let seg_tree = SegTree::<SumSpec>::from_slice(&values);
+
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                assert_eq!(
+                    seg_tree.query_with(l..r, 0, |acc, v| acc + v),
+                    seg_tree.query(l..r)
+                );
+            }
+        }
+
+        let words: Vec<String> = ["a", "b", "c", "d", "e", "f"].map(String::from).to_vec();
+        let concat_tree = SegTree::<ConcatSpec>::from_slice(&words);
+        let joined = concat_tree.query_with(1..6, String::new(), |mut acc, v| {
+            acc.push_str(v);
+            acc
+        });
+        assert_eq!(joined, "bcdef");
+    }
+
+    #[test]
+    fn test_lower_bound_against_linear_scan() {
+        let weights: Vec<i64> = vec![3, 0, 0, 5, 1, 0, 2, 4, 0, 7];
+        let seg_tree = SegTree::<SumSpec>::from_slice(&weights);
+        let total: i64 = weights.iter().sum();
+
+        for target in 0..=total + 1 {
+            let mut prefix = 0;
+            let expected = weights
+                .iter()
+                .position(|w| {
+                    prefix += w;
+                    prefix >= target
+                })
+                .unwrap_or(weights.len());
+            assert_eq!(seg_tree.lower_bound(target), expected, "target {}", target);
+        }
+    }
+
+    #[test]
+    fn test_descend_implements_lower_bound() {
+        let weights: Vec<i64> = vec![3, 0, 0, 5, 1, 0, 2, 4, 0, 7, 0];
+        let seg_tree = SegTree::<SumSpec>::from_slice(&weights);
+        let total: i64 = weights.iter().sum();
+
+        let lower_bound = |target: i64| {
+            seg_tree
+                .descend(.., |acc, node| acc + node >= target)
+                .unwrap_or(weights.len())
+        };
+        for target in 0..=total + 1 {
+            assert_eq!(
+                lower_bound(target),
+                seg_tree.lower_bound(target),
+                "target {}",
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn test_descend_within_range() {
+        let weights: Vec<i64> = vec![2, 7, 1, 0, 3, 3, 6, 1, 4];
+        let seg_tree = SegTree::<SumSpec>::from_slice(&weights);
+
+        for l in 0..=weights.len() {
+            for r in l..=weights.len() {
+                for target in 0..=20 {
+                    let mut prefix = 0;
+                    let expected = (l..r).find(|&i| {
+                        prefix += weights[i];
+                        prefix >= target
+                    });
+                    let found = seg_tree.descend(l..r, |acc, node| acc + node >= target);
+                    assert_eq!(found, expected, "[{}, {}) target {}", l, r, target);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5]);
+
+        assert_eq!(seg_tree.query(..), 15);
+
+        // Update index 2 (value 3) to 10
+        seg_tree.update(2, 10);
+        assert_eq!(seg_tree.query(..), 1 + 2 + 10 + 4 + 5);
+        assert_eq!(seg_tree.query(2..3), 10);
+        assert_eq!(seg_tree.query(..2), 3);
+    }
+
+    #[test]
+    fn test_apply_range() {
+        let values: Vec<i64> = (1..=11).collect();
+        let mut expected = values.clone();
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&values);
+
+        seg_tree.apply_range(3..8, |x| *x += 10);
+        expected[3..8].iter_mut().for_each(|x| *x += 10);
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                assert_eq!(seg_tree.query(l..r), expected[l..r].iter().sum::<i64>());
+            }
+        }
+
+        seg_tree.apply_range(5..5, |x| *x = 1000);
+        assert_eq!(seg_tree.query(..), expected.iter().sum::<i64>());
+
+        let cp = seg_tree.checkpoint();
+        seg_tree.apply_range(.., |x| *x = 0);
+        assert_eq!(seg_tree.query(..), 0);
+        seg_tree.rollback(cp);
+        assert_eq!(seg_tree.query(..), expected.iter().sum::<i64>());
+    }
+
+    #[test]
+    fn test_replace_returns_previous_value() {
+        let values = vec![4, 8, 15, 16, 23, 42];
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&values);
+        let mut external_sum: i64 = values.iter().sum();
 
-        assert_eq!(seg_tree.query(1..1), 0);
-        assert_eq!(seg_tree.query(3..3), 0);
+        for (i, new) in [(2, 100), (5, -1), (2, 7)] {
+            let before = *seg_tree.get(i);
+            let old = seg_tree.replace(i, new);
+            assert_eq!(old, before);
+            assert_eq!(*seg_tree.get(i), new);
+            external_sum += new - old;
+            assert_eq!(seg_tree.query(..), external_sum);
+        }
+
+        let cp = seg_tree.checkpoint();
+        seg_tree.replace(0, 1000);
+        seg_tree.rollback(cp);
+        assert_eq!(seg_tree.query(..), external_sum);
     }
 
     #[test]
-    fn test_update() {
-        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5]);
+    #[should_panic(expected = "replace index out of bounds")]
+    fn test_panic_replace_out_of_bounds() {
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&[1, 2]);
+        seg_tree.replace(2, 0);
+    }
 
-        assert_eq!(seg_tree.query(..), 15);
+    #[test]
+    fn test_modify_all() {
+        let values: Vec<i64> = (1..=10).collect();
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&values);
 
-        // Update index 2 (value 3) to 10
-        seg_tree.update(2, 10);
-        assert_eq!(seg_tree.query(..), 1 + 2 + 10 + 4 + 5);
-        assert_eq!(seg_tree.query(2..3), 10);
-        assert_eq!(seg_tree.query(..2), 3);
+        seg_tree.modify_all(|_, x| *x *= 2);
+        assert_eq!(seg_tree.query(..), 110);
+        assert_eq!(seg_tree.query(2..5), 24);
+
+        let mut visited = Vec::new();
+        seg_tree.modify_all(|i, x| {
+            visited.push(i);
+            *x -= i as i64;
+        });
+        assert_eq!(visited, (0..10).collect::<Vec<_>>());
+        assert_eq!(seg_tree.query(..), 110 - 45);
+        assert_eq!(
+            seg_tree,
+            SegTree::<SumSpec>::from_slice(&[2, 3, 4, 5, 6, 7, 8, 9, 10, 11])
+        );
     }
 
     #[test]
@@ -371,6 +3244,681 @@ mod tests {
         assert_eq!(seg_tree.query(..500), 125250 + 500);
     }
 
+    #[test]
+    fn test_resize_grow_across_power_of_two() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec((1..=1000).collect());
+        seg_tree.resize(2000, 1);
+
+        // The first 1000 values survive, the new 1000 are filled with 1
+        assert_eq!(seg_tree.query(..1000), 500500);
+        assert_eq!(seg_tree.query(1000..), 1000);
+        assert_eq!(seg_tree.query(..), 501500);
+        assert_eq!(seg_tree.query(998..1002), 999 + 1000 + 1 + 1);
+
+        seg_tree.update(1999, 5);
+        assert_eq!(seg_tree.query(1999..), 5);
+    }
+
+    #[test]
+    fn test_resize_shrink() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec((1..=10).collect());
+
+        // Same power of two (16 leaves)
+        seg_tree.resize(9, 0);
+        assert_eq!(seg_tree.query(..), 45);
+
+        // Smaller power of two (4 leaves)
+        seg_tree.resize(3, 0);
+        assert_eq!(seg_tree.query(..), 6);
+        assert_eq!(seg_tree.query(1..), 5);
+
+        // Growing again must not resurrect dropped values
+        seg_tree.resize(5, 0);
+        assert_eq!(seg_tree.query(..), 6);
+        assert_eq!(seg_tree.query(3..), 0);
+    }
+
+    #[test]
+    fn test_push_running_aggregate() {
+        let mut seg_tree = SegTree::<SumSpec>::new(0);
+        let mut running = 0;
+
+        for i in 1..=1000 {
+            seg_tree.push(i);
+            running += i;
+            assert_eq!(seg_tree.query(..), running);
+        }
+        assert_eq!(seg_tree.query(500..), (501..=1000).sum::<i64>());
+    }
+
+    #[test]
+    fn test_with_capacity_push_does_not_reallocate() {
+        let mut seg_tree = SegTree::<SumSpec>::with_capacity(1000);
+        let backing = seg_tree.data.as_ptr();
+        assert_eq!(seg_tree.query(..), 0);
+
+        for i in 1..=1000 {
+            seg_tree.push(i);
+        }
+        assert_eq!(seg_tree.data.as_ptr(), backing);
+        assert_eq!(seg_tree.query(..), 500_500);
+
+        // The rounded-up capacity is used before growing
+        for i in 1001..=1024 {
+            seg_tree.push(i);
+        }
+        assert_eq!(seg_tree.data.as_ptr(), backing);
+        seg_tree.push(0);
+        assert_eq!(seg_tree.max_size, 2048);
+    }
+
+    #[test]
+    fn test_reset_from_slice_reuses_allocation() {
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&[1; 16]);
+        let backing = seg_tree.data.as_ptr();
+
+        for len in [16, 3, 9, 0, 12] {
+            let values: Vec<i64> = (0..len).map(|i| i * 7 - 20).collect();
+            seg_tree.reset_from_slice(&values);
+            assert_eq!(seg_tree.data.as_ptr(), backing);
+            assert_eq!(seg_tree.len(), values.len());
+            for l in 0..=values.len() {
+                for r in l..=values.len() {
+                    assert_eq!(seg_tree.query(l..r), values[l..r].iter().sum::<i64>());
+                }
+            }
+            assert_eq!(seg_tree, SegTree::<SumSpec>::from_slice(&values));
+        }
+
+        // Outgrowing the capacity reallocates
+        let values: Vec<i64> = (0..17).collect();
+        seg_tree.reset_from_slice(&values);
+        assert_eq!(seg_tree.capacity(), 32);
+        assert_eq!(seg_tree.query(..), 136);
+    }
+
+    #[test]
+    fn test_reserve_and_shrink_to_fit() {
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&[5, 6, 7]);
+        seg_tree.reserve(1000);
+        assert_eq!(seg_tree.capacity(), 1024);
+        let backing = seg_tree.data.as_ptr();
+
+        for i in 1..=1000 {
+            seg_tree.push(i);
+        }
+        assert_eq!(seg_tree.data.as_ptr(), backing);
+        assert_eq!(seg_tree.query(..), 18 + 500_500);
+
+        // Already large enough: no-op
+        seg_tree.reserve(21);
+        assert_eq!(seg_tree.data.as_ptr(), backing);
+
+        while seg_tree.len() > 10 {
+            seg_tree.pop();
+        }
+        let internal_len = seg_tree.internal_len();
+        seg_tree.shrink_to_fit();
+        assert!(seg_tree.internal_len() < internal_len);
+        assert_eq!(seg_tree.capacity(), 16);
+        assert_eq!(
+            seg_tree,
+            SegTree::<SumSpec>::from_slice(&[5, 6, 7, 1, 2, 3, 4, 5, 6, 7])
+        );
+        assert_eq!(seg_tree.query(2..6), 7 + 1 + 2 + 3);
+    }
+
+    #[test]
+    fn test_grow_in_place_matches_fresh_build() {
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&[3, -1, 4, 1]);
+        seg_tree.grow_in_place();
+        assert_eq!(seg_tree.capacity(), 8);
+
+        // Every node, padding included, matches a tree laid out at capacity 8
+        let mut fresh = SegTree::<SumSpec>::with_capacity(8);
+        fresh.extend([3, -1, 4, 1]);
+        assert_eq!(seg_tree.data, fresh.data);
+
+        for v in [5, -9, 2, 6] {
+            seg_tree.push(v);
+        }
+        let values = [3, -1, 4, 1, 5, -9, 2, 6];
+        let fresh = SegTree::<SumSpec>::from_slice(&values);
+        assert_eq!(seg_tree.capacity(), 8);
+        for l in 0..=values.len() {
+            for r in l..=values.len() {
+                assert_eq!(seg_tree.query(l..r), fresh.query(l..r));
+            }
+        }
+
+        // Doubling from the minimal empty layout
+        let mut empty = SegTree::<SumSpec>::new(0);
+        empty.grow_in_place();
+        assert_eq!(empty.capacity(), 2);
+        assert_eq!(empty.query(..), 0);
+    }
+
+    #[test]
+    fn test_default_is_empty() {
+        let mut seg_tree = SegTree::<SumSpec>::default();
+        assert_eq!(seg_tree.query(..), 0);
+        assert_eq!(seg_tree.total(), 0);
+        assert_eq!(seg_tree.try_query(..1), None);
+        seg_tree.push(7);
+        assert_eq!(seg_tree.query(..), 7);
+        assert_eq!(seg_tree.total(), 7);
+    }
+
+    #[test]
+    fn test_clear_keeps_storage() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec((1..=10).collect());
+        let backing = seg_tree.data.as_ptr();
+
+        seg_tree.clear();
+        assert_eq!(seg_tree.data.as_ptr(), backing);
+        assert_eq!(seg_tree.len(), 10);
+        assert_eq!(seg_tree.query(..), 0);
+        assert!(seg_tree.data.iter().all(|&x| x == 0));
+
+        seg_tree.update(4, 5);
+        assert_eq!(seg_tree.query(..), 5);
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![9, 8, 7, 6, 5]);
+        seg_tree.fill(3);
+        assert_eq!(seg_tree.query(..), 15);
+        assert_eq!(seg_tree.query(1..3), 6);
+
+        // Padding leaves stay at the identity
+        seg_tree.push(1);
+        assert_eq!(seg_tree.query(..), 16);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut seg_tree = SegTree::<SumSpec>::default();
+        seg_tree.extend(1..=500);
+        assert_eq!(seg_tree.len(), 500);
+        assert_eq!(seg_tree.query(..), 125_250);
+        assert_eq!(seg_tree.query(100..200), (101..=200).sum::<i64>());
+
+        // Within the current capacity only the new path is recomputed
+        seg_tree.extend(vec![1000; 12]);
+        assert_eq!(seg_tree.max_size, 512);
+        assert_eq!(seg_tree.query(..), 137_250);
+        assert_eq!(seg_tree.query(499..), 12_500);
+
+        seg_tree.extend(core::iter::empty());
+        assert_eq!(seg_tree.len(), 512);
+    }
+
+    #[test]
+    fn test_map_into_other_spec() {
+        use crate::helpers::{SegTreeSum, SegTreeSumSpec};
+
+        let values: Vec<i32> = (1..=100).map(|x| x * 1000).collect();
+        let tree = SegTreeSum::<i32>::from_slice(&values);
+        let squares = tree.map::<SegTreeSumSpec<i64>, _>(|&x| i64::from(x) * i64::from(x));
+
+        let expected: i64 = values.iter().map(|&x| i64::from(x) * i64::from(x)).sum();
+        assert_eq!(squares.len(), tree.len());
+        assert_eq!(squares.query(..), expected);
+        assert_eq!(squares.query(..2), 5_000_000);
+        assert!(expected > i64::from(i32::MAX)); // would overflow the i32 tree
+    }
+
+    #[test]
+    fn test_concat() {
+        let left = SegTree::<SumSpec>::from_vec(vec![1, 2, 3]);
+        let right = SegTree::<SumSpec>::from_vec(vec![4, 5]);
+        let tree = left.concat(right);
+
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.query(..), 15);
+        assert_eq!(tree.query(2..4), 7);
+        assert_eq!(tree.query(1..5), 14);
+        assert!(tree == SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5]));
+
+        let empty = SegTree::<SumSpec>::new(0);
+        assert!(empty.concat(tree.clone()) == tree);
+    }
+
+    #[test]
+    fn test_split_and_concat_round_trip() {
+        let values: Vec<i64> = (1..=11).collect();
+        let tree = SegTree::<SumSpec>::from_slice(&values);
+
+        for at in 0..=values.len() {
+            let (left, right) = tree.clone().split(at);
+            assert_eq!(left.len(), at);
+            assert_eq!(right.len(), values.len() - at);
+            for l in 0..=at {
+                assert_eq!(left.query(l..), values[l..at].iter().sum::<i64>());
+            }
+            for r in 0..=right.len() {
+                assert_eq!(right.query(..r), values[at..at + r].iter().sum::<i64>());
+            }
+            assert!(left.concat(right) == tree);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "split index out of bounds")]
+    fn test_panic_split_out_of_bounds() {
+        SegTree::<SumSpec>::new(3).split(4);
+    }
+
+    #[test]
+    fn test_checkpoint_rollback_restores_exact_state() {
+        let values: Vec<i64> = (1..=20).collect();
+        let original = SegTree::<SumSpec>::from_slice(&values);
+        let mut seg_tree = original.clone();
+
+        let outer = seg_tree.checkpoint();
+        seg_tree.update(3, 100);
+        seg_tree.update(3, 200);
+        seg_tree.update(19, -5);
+        let after_first = seg_tree.clone();
+
+        let inner = seg_tree.checkpoint();
+        seg_tree.update(0, 7);
+        seg_tree.update(10, 0);
+        seg_tree.rollback(inner);
+        assert_eq!(seg_tree.data, after_first.data);
+
+        seg_tree.rollback(outer);
+        assert_eq!(seg_tree.data, original.data);
+        assert_eq!(seg_tree.query(..), 210);
+
+        // Checkpoints can be reused after rolling back
+        seg_tree.update(5, 0);
+        seg_tree.rollback(outer);
+        assert_eq!(seg_tree.data, original.data);
+    }
+
+    #[test]
+    #[should_panic(expected = "Checkpoint is no longer valid")]
+    fn test_panic_rollback_after_structural_change() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3]);
+        let cp = seg_tree.checkpoint();
+        seg_tree.update(0, 5);
+        seg_tree.pop();
+        seg_tree.checkpoint();
+        seg_tree.rollback(cp);
+    }
+
+    #[test]
+    #[should_panic(expected = "Checkpoint is no longer valid")]
+    fn test_panic_rollback_past_checkpoint() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3]);
+        let outer = seg_tree.checkpoint();
+        seg_tree.update(0, 5);
+        let inner = seg_tree.checkpoint();
+        seg_tree.rollback(outer);
+        seg_tree.rollback(inner);
+    }
+
+    #[test]
+    fn test_layout_accessors_match_get() {
+        for size in [0, 1, 5, 8, 13] {
+            let seg_tree = SegTree::<SumSpec>::from_vec((0..size as i64).map(|x| x * 3).collect());
+            assert_eq!(seg_tree.internal_len(), 2 * seg_tree.capacity());
+            for i in 0..size {
+                assert!(core::ptr::eq(
+                    &seg_tree.data[seg_tree.leaf_offset() + i],
+                    seg_tree.get(i)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pop() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5]);
+
+        assert_eq!(seg_tree.pop(), Some(5));
+        assert_eq!(seg_tree.pop(), Some(4));
+        assert_eq!(seg_tree.query(..), 6);
+
+        // Popped slots are reset, so pushing again starts from scratch
+        seg_tree.push(10);
+        assert_eq!(seg_tree.query(..), 16);
+        assert_eq!(seg_tree.query(3..), 10);
+
+        while seg_tree.pop().is_some() {}
+        assert_eq!(seg_tree.pop(), None);
+        assert_eq!(seg_tree.query(..), 0);
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let original = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4]);
+        let mut copy = original.clone();
+
+        copy.update(0, 100);
+        copy.push(5);
+        assert_eq!(copy.query(..), 114);
+        assert_eq!(original.query(..), 10);
+        assert_eq!(original.query(..1), 1);
+    }
+
+    #[test]
+    fn test_eq_by_logical_contents() {
+        let values: Vec<i64> = (1..=10).collect();
+        let built = SegTree::<SumSpec>::from_vec(values.clone());
+
+        let mut pushed = SegTree::<SumSpec>::new(0);
+        for v in values {
+            pushed.push(v);
+        }
+        assert!(built == pushed);
+
+        pushed.update(3, 0);
+        assert!(built != pushed);
+        pushed.pop();
+        assert!(built != pushed);
+    }
+
+    #[test]
+    fn test_debug_output() {
+        let seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2]);
+        let output = format!("{:?}", seg_tree);
+
+        assert!(output.starts_with("SegTree"));
+        assert!(output.contains("size: 2"));
+        assert!(output.contains("data: [0, 3, 1, 2]"));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_try_query() {
+        let seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3, 4, 5]);
+
+        assert_eq!(seg_tree.try_query(..), Some(15));
+        assert_eq!(seg_tree.try_query(1..4), Some(9));
+        assert_eq!(seg_tree.try_query(2..2), Some(0));
+        assert_eq!(seg_tree.try_query(4..3), None); // left > right
+        assert_eq!(seg_tree.try_query(..6), None); // right > size
+        assert_eq!(seg_tree.try_query(6..), None);
+        assert_eq!(seg_tree.try_query(..=usize::MAX), None);
+    }
+
+    #[test]
+    fn test_query_every_bound_pair() {
+        use core::ops::Bound::{self, Excluded, Included, Unbounded};
+
+        let values: Vec<i64> = (0..8).map(|i| 1 << i).collect();
+        let seg_tree = SegTree::<SumSpec>::from_slice(&values);
+
+        let mut bounds = vec![Unbounded];
+        for i in 0..=values.len() {
+            bounds.extend([Included(i), Excluded(i)]);
+        }
+        for &start in &bounds {
+            for &end in &bounds {
+                let l = match start {
+                    Included(s) => s,
+                    Excluded(s) => s + 1,
+                    Unbounded => 0,
+                };
+                let r = match end {
+                    Included(e) => e + 1,
+                    Excluded(e) => e,
+                    Unbounded => values.len(),
+                };
+                let pair: (Bound<usize>, Bound<usize>) = (start, end);
+                if l <= r && r <= values.len() {
+                    // Distinct powers of two pin down exactly which leaves were summed
+                    let expected = values[l..r].iter().sum::<i64>();
+                    assert_eq!(seg_tree.query(pair), expected, "bounds {:?}", pair);
+                } else {
+                    assert_eq!(seg_tree.try_query(pair), None, "bounds {:?}", pair);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_prefix_and_suffix() {
+        let values = [3, 1, 4, 1, 5, 9, 2];
+        let seg_tree = SegTree::<SumSpec>::from_slice(&values);
+
+        assert_eq!(seg_tree.prefix(3), 3 + 1 + 4);
+        for i in 0..=values.len() {
+            assert_eq!(seg_tree.prefix(i), values[..i].iter().sum::<i64>());
+            assert_eq!(seg_tree.suffix(i), values[i..].iter().sum::<i64>());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_prefix_past_end() {
+        SegTree::<SumSpec>::from_slice(&[1, 2, 3]).prefix(4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_suffix_past_end() {
+        SegTree::<SumSpec>::from_slice(&[1, 2, 3]).suffix(4);
+    }
+
+    #[test]
+    fn test_swap_and_swap_back() {
+        let values: Vec<String> = ["a", "b", "c", "d", "e", "f"].map(String::from).to_vec();
+        let mut seg_tree = SegTree::<ConcatSpec>::from_slice(&values);
+        let original = seg_tree.clone();
+
+        for (i, j) in [(0, 5), (1, 2), (4, 3), (2, 2)] {
+            seg_tree.swap(i, j);
+            let mut swapped = values.clone();
+            swapped.swap(i, j);
+            for l in 0..=values.len() {
+                for r in l..=values.len() {
+                    assert_eq!(seg_tree.query(l..r), swapped[l..r].concat());
+                }
+            }
+
+            seg_tree.swap(j, i);
+            assert_eq!(seg_tree.data, original.data);
+        }
+    }
+
+    #[test]
+    fn test_swap_rolls_back() {
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&[1, 20, 300]);
+        let checkpoint = seg_tree.checkpoint();
+        seg_tree.swap(0, 2);
+        seg_tree.update(1, 0);
+        assert_eq!(seg_tree.query(..2), 300);
+        seg_tree.rollback(checkpoint);
+        assert_eq!(seg_tree, SegTree::<SumSpec>::from_slice(&[1, 20, 300]));
+    }
+
+    #[test]
+    #[should_panic(expected = "swap index out of bounds")]
+    fn test_swap_out_of_bounds() {
+        SegTree::<SumSpec>::from_slice(&[1, 2, 3]).swap(0, 3);
+    }
+
+    #[test]
+    fn test_combine_matches_query_order() {
+        let values: Vec<String> = ["ab", "c", "", "de", "f"].map(String::from).to_vec();
+        let seg_tree = SegTree::<ConcatSpec>::from_slice(&values);
+
+        for i in 0..values.len() - 1 {
+            let pair = SegTree::<ConcatSpec>::combine(
+                &seg_tree.query(i..i + 1),
+                &seg_tree.query(i + 1..i + 2),
+            );
+            assert_eq!(pair, seg_tree.query(i..i + 2));
+        }
+        for l in 0..=values.len() {
+            for m in l..=values.len() {
+                for r in m..=values.len() {
+                    let joined = SegTree::<ConcatSpec>::combine(
+                        &seg_tree.query(l..m),
+                        &seg_tree.query(m..r),
+                    );
+                    assert_eq!(joined, seg_tree.query(l..r));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_equal_trees_hash_equally() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash_of(tree: &SegTree<SumSpec>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            tree.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let built = SegTree::<SumSpec>::from_slice(&[4, 0, 2]);
+        let mut pushed = SegTree::<SumSpec>::new(0);
+        for v in [4, 0, 2, 9, 9] {
+            pushed.push(v);
+        }
+        pushed.pop();
+        pushed.pop();
+        let mut updated = SegTree::<SumSpec>::new(3);
+        updated.update(0, 4);
+        updated.update(2, 2);
+
+        assert_ne!(built.capacity(), pushed.capacity());
+        assert_eq!(built, pushed);
+        assert_eq!(hash_of(&built), hash_of(&pushed));
+        assert_eq!(hash_of(&built), hash_of(&updated));
+
+        // A trailing identity element is still part of the contents
+        let longer = SegTree::<SumSpec>::from_slice(&[4, 0, 2, 0]);
+        assert_ne!(built, longer);
+        assert_ne!(hash_of(&built), hash_of(&longer));
+    }
+
+    #[test]
+    fn test_combine_range_on_max_tree() {
+        use crate::helpers::SegTreeMaxSpec;
+
+        let mut values = vec![4, -1, 8, 0, 6, 2, 9];
+        let mut seg_tree = SegTree::<SegTreeMaxSpec<i32>>::from_slice(&values);
+
+        for (l, r, v) in [(1, 5, 5), (0, 2, 7), (3, 7, 10), (2, 3, -5)] {
+            seg_tree.combine_range(l..r, v);
+            for x in &mut values[l..r] {
+                *x = (*x).max(v);
+            }
+            for (i, x) in values.iter().enumerate() {
+                assert_eq!(seg_tree.get(i), x);
+            }
+            for a in 0..values.len() {
+                for b in a + 1..=values.len() {
+                    assert_eq!(seg_tree.query(a..b), *values[a..b].iter().max().unwrap());
+                }
+            }
+        }
+        assert_eq!(values, [7, 7, 8, 10, 10, 10, 10]);
+    }
+
+    #[test]
+    fn test_changed_since_reports_exactly_updated_indices() {
+        let mut seg_tree = SegTree::<SumSpec>::from_slice(&[5, 6, 7, 8, 9, 10, 11]);
+        let snap = seg_tree.snapshot();
+        assert!(seg_tree.changed_since(&snap).is_empty());
+
+        seg_tree.update(4, 0);
+        seg_tree.update(1, 60);
+        seg_tree.update(6, 11); // unchanged value
+        seg_tree.update(2, 70);
+        seg_tree.update(2, 7); // changed back
+        assert_eq!(seg_tree.changed_since(&snap), vec![1, 4]);
+
+        // Restoring the values clears the diff
+        seg_tree.update(4, 9);
+        seg_tree.update(1, 6);
+        assert!(seg_tree.changed_since(&snap).is_empty());
+
+        // Length changes report the indices present on one side only
+        seg_tree.pop();
+        seg_tree.pop();
+        assert_eq!(seg_tree.changed_since(&snap), vec![5, 6]);
+        let shorter = seg_tree.snapshot();
+        seg_tree.extend([1, 2, 3]);
+        assert_eq!(seg_tree.changed_since(&shorter), vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn test_query_rel_matches_python_slicing() {
+        let values = [3, 1, 4, 1, 5, 9, 2, 6];
+        let seg_tree = SegTree::<SumSpec>::from_slice(&values);
+        let size = values.len();
+
+        assert_eq!(
+            seg_tree.query_rel(-3, -1),
+            seg_tree.query(size - 3..size - 1)
+        );
+        let n = size as isize;
+        for start in -n..=n {
+            for end in -n..=n {
+                let l = if start < 0 { start + n } else { start } as usize;
+                let r = if end < 0 { end + n } else { end } as usize;
+                if l <= r {
+                    assert_eq!(seg_tree.query_rel(start, end), seg_tree.query(l..r));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_query_rel_rejects_index_before_start() {
+        SegTree::<SumSpec>::from_slice(&[1, 2, 3]).query_rel(-4, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_query_rel_rejects_index_past_end() {
+        SegTree::<SumSpec>::from_slice(&[1, 2, 3]).query_rel(0, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_query_rel_rejects_crossed_bounds() {
+        SegTree::<SumSpec>::from_slice(&[1, 2, 3]).query_rel(-1, 1);
+    }
+
+    #[test]
+    fn test_try_update_and_get() {
+        let mut seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 2, 3]);
+
+        assert_eq!(seg_tree.try_update(1, 10), Some(()));
+        assert_eq!(seg_tree.try_update(3, 10), None);
+        assert_eq!(seg_tree.query(..), 14);
+
+        assert_eq!(seg_tree.try_get(1), Some(&10));
+        assert_eq!(seg_tree.try_get(3), None);
+        assert_eq!(*seg_tree.get(2), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_display_skips_identity_nodes() {
+        let seg_tree = SegTree::<SumSpec>::from_vec(vec![1, 0, 3]);
+        let output = seg_tree.to_string();
+
+        assert!(output.starts_with("SegTree {"));
+        assert!(output.contains("Size: 3 (Internal: 4)"));
+        assert!(output.contains("4 (Index: 1, Covers [0, 4))"));
+        assert!(output.contains("1 (Index: 2, Covers [0, 2))"));
+        assert!(output.contains("3 (Index: 6, Covers [2, 3))"));
+        assert!(!output.contains("Index: 5,")); // the zero leaf
+        assert!(!output.contains("Index: 7,")); // padding
+    }
+
     #[test]
     #[should_panic(expected = "update index out of bounds")]
     fn test_panic_update_out_of_bounds() {
@@ -392,4 +3940,19 @@ mod tests {
         let seg_tree = SegTree::<SumSpec>::new(10);
         seg_tree.query(5..4);
     }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_query_inclusive_end_at_usize_max() {
+        let seg_tree = SegTree::<SumSpec>::new(4);
+        seg_tree.query(2..=usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_query_excluded_start_at_usize_max() {
+        use core::ops::Bound::{Excluded, Unbounded};
+        let seg_tree = SegTree::<SumSpec>::new(4);
+        seg_tree.query((Excluded(usize::MAX), Unbounded));
+    }
 }
@@ -0,0 +1,690 @@
+//! Segment tree beats for range clamp updates with sum queries.
+//!
+//! Range chmin/chmax (`a[i] = min(a[i], x)`) do not distribute over sums, so
+//! they cannot be expressed as a [`LazySegTreeSpec`](crate::LazySegTreeSpec).
+//! Segment tree beats stores the extremum, the strict second extremum and the
+//! number of extremal elements per node, and only descends while `x` would
+//! change more than one distinct value of a node. This gives amortized
+//! O(log² n) updates and O(log n) queries.
+//!
+//! - [`SegTreeBeatsChmaxSum`]: range chmax with range sum
+//! - [`SegTreeBeats`]: range chmin and range add with range sum
+//!
+//! # Example
+//!
+//! ```rust
+//! use array_range_query::{SegTreeBeats, SegTreeBeatsChmaxSum};
+//!
+//! let mut tree = SegTreeBeatsChmaxSum::<i64>::from_vec(vec![1, 5, 2, 8, 3]);
+//! tree.chmax(.., 4); // [4, 5, 4, 8, 4]
+//! assert_eq!(tree.query(..), 25);
+//! assert_eq!(tree.query(1..3), 9);
+//!
+//! let mut tree = SegTreeBeats::<i64>::from_vec(vec![1, 5, 2, 8, 3]);
+//! tree.chmin(.., 4); // [1, 4, 2, 4, 3]
+//! tree.add(1..3, 10); // [1, 14, 12, 4, 3]
+//! assert_eq!(tree.query(..), 34);
+//! ```
+
+use crate::{utils, SegTreeNode};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::ops::{Add, Mul, RangeBounds, Sub};
+use min_max_traits::Max as ConstUpperBound;
+use num_traits::{ConstZero, NumCast};
+
+/// Per-node state for [`SegTreeBeatsChmaxSum`].
+#[derive(Clone, Debug)]
+struct ChmaxNode<T> {
+    sum: T,
+    min: T,
+    second_min: T,
+    min_count: usize,
+}
+
+/// Segment tree beats supporting range chmax updates and range sum queries.
+///
+/// Padding leaves hold `T::MAX` as their minimum with a count of zero, so
+/// they never contribute to sums and are never raised by `chmax`.
+///
+/// # Examples
+///
+/// ```
+/// use array_range_query::SegTreeBeatsChmaxSum;
+///
+/// let mut tree = SegTreeBeatsChmaxSum::<i32>::from_vec(vec![3, 1, 4, 1, 5]);
+/// tree.chmax(1..4, 2); // [3, 2, 4, 2, 5]
+/// assert_eq!(tree.query(..), 16);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SegTreeBeatsChmaxSum<T> {
+    size: usize,
+    max_depth: u32,
+    nodes: Box<[ChmaxNode<T>]>,
+}
+
+impl<T> SegTreeBeatsChmaxSum<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+    T: ConstZero + ConstUpperBound + NumCast,
+{
+    // ===== CONSTRUCTORS =====
+
+    /// Creates a new tree with `size` zero-valued elements.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn new(size: usize) -> Self {
+        Self::from_vec(vec![T::ZERO; size])
+    }
+
+    /// Creates a new tree from a slice of values.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_slice(values: &[T]) -> Self {
+        Self::from_vec(values.to_vec())
+    }
+
+    /// Creates a new tree from a vector of values.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_vec(values: Vec<T>) -> Self {
+        let size = values.len();
+        let max_size = size.next_power_of_two();
+        let max_depth = max_size.trailing_zeros();
+        let mut nodes = vec![Self::padding(); 2 * max_size];
+
+        for (i, v) in values.into_iter().enumerate() {
+            nodes[max_size + i] = Self::leaf(v);
+        }
+
+        let mut tree = Self {
+            size,
+            max_depth,
+            nodes: nodes.into_boxed_slice(),
+        };
+        for i in (1..max_size).rev() {
+            tree.pull(SegTreeNode(i));
+        }
+        tree
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Returns the sum of the elements in the given range.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        if left == right {
+            return T::ZERO;
+        }
+        self.query_node(SegTreeNode(1), self.nodes[1].clone(), left, right)
+    }
+
+    /// Replaces every element `a[i]` in the range with `max(a[i], x)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeBeatsChmaxSum;
+    ///
+    /// let mut tree = SegTreeBeatsChmaxSum::<i64>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.chmax(..3, 3); // [3, 3, 3, 4]
+    /// assert_eq!(tree.query(..), 13);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// Amortized O(log² n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn chmax<R: RangeBounds<usize>>(&mut self, range: R, x: T) {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        if left == right {
+            return;
+        }
+        self.chmax_node(SegTreeNode(1), left, right, x);
+    }
+
+    // ===== PRIVATE HELPER METHODS =====
+
+    fn leaf(value: T) -> ChmaxNode<T> {
+        ChmaxNode {
+            sum: value,
+            min: value,
+            second_min: T::MAX,
+            min_count: 1,
+        }
+    }
+
+    fn padding() -> ChmaxNode<T> {
+        ChmaxNode {
+            sum: T::ZERO,
+            min: T::MAX,
+            second_min: T::MAX,
+            min_count: 0,
+        }
+    }
+
+    fn count_as_t(count: usize) -> T {
+        T::from(count).unwrap_or_else(|| panic!("Failed to convert usize to T"))
+    }
+
+    /// Raises the minimum of a node to `x`, assuming `x < second_min`.
+    fn apply_chmax(node: &mut ChmaxNode<T>, x: T) {
+        if node.min < x {
+            node.sum = node.sum + (x - node.min) * Self::count_as_t(node.min_count);
+            node.min = x;
+        }
+    }
+
+    fn chmax_node(&mut self, node: SegTreeNode, left: usize, right: usize, x: T) {
+        let (node_left, node_right) = node.node_bounds(self.max_depth);
+        if right <= node_left || node_right <= left || self.nodes[node.0].min >= x {
+            return;
+        }
+        if left <= node_left
+            && node_right <= right
+            && (node.is_leaf(self.max_depth) || x < self.nodes[node.0].second_min)
+        {
+            Self::apply_chmax(&mut self.nodes[node.0], x);
+            return;
+        }
+
+        self.push(node);
+        self.chmax_node(node.left_child(), left, right, x);
+        self.chmax_node(node.right_child(), left, right, x);
+        self.pull(node);
+    }
+
+    /// Sums `[left, right)` inside `node`, whose effective state is `state`.
+    ///
+    /// Pending chmax values are pushed into copies of the children instead of
+    /// the tree itself, so queries never mutate.
+    fn query_node(&self, node: SegTreeNode, state: ChmaxNode<T>, left: usize, right: usize) -> T {
+        let (node_left, node_right) = node.node_bounds(self.max_depth);
+        if right <= node_left || node_right <= left {
+            return T::ZERO;
+        }
+        if left <= node_left && node_right <= right {
+            return state.sum;
+        }
+
+        let mut left_state = self.nodes[node.left_child().0].clone();
+        let mut right_state = self.nodes[node.right_child().0].clone();
+        Self::apply_chmax(&mut left_state, state.min);
+        Self::apply_chmax(&mut right_state, state.min);
+        self.query_node(node.left_child(), left_state, left, right)
+            + self.query_node(node.right_child(), right_state, left, right)
+    }
+
+    fn push(&mut self, node: SegTreeNode) {
+        let min = self.nodes[node.0].min;
+        Self::apply_chmax(&mut self.nodes[node.left_child().0], min);
+        Self::apply_chmax(&mut self.nodes[node.right_child().0], min);
+    }
+
+    fn pull(&mut self, node: SegTreeNode) {
+        let l = &self.nodes[node.left_child().0];
+        let r = &self.nodes[node.right_child().0];
+        let (min, second_min, min_count) = match l.min.cmp(&r.min) {
+            core::cmp::Ordering::Equal => (
+                l.min,
+                l.second_min.min(r.second_min),
+                l.min_count + r.min_count,
+            ),
+            core::cmp::Ordering::Less => (l.min, l.second_min.min(r.min), l.min_count),
+            core::cmp::Ordering::Greater => (r.min, r.second_min.min(l.min), r.min_count),
+        };
+        self.nodes[node.0] = ChmaxNode {
+            sum: l.sum + r.sum,
+            min,
+            second_min,
+            min_count,
+        };
+    }
+}
+
+/// Per-node state for [`SegTreeBeats`].
+///
+/// `add` has already been applied to this node and is pending for its
+/// children. `len` counts logical elements, so padding nodes have `len == 0`.
+#[derive(Clone, Debug)]
+struct ChminAddNode<T> {
+    sum: T,
+    max: T,
+    second_max: Option<T>,
+    max_count: usize,
+    len: usize,
+    add: T,
+}
+
+/// Segment tree beats supporting range chmin, range add and range sum.
+///
+/// # Examples
+///
+/// ```
+/// use array_range_query::SegTreeBeats;
+///
+/// let mut tree = SegTreeBeats::<i32>::from_vec(vec![3, 1, 4, 1, 5]);
+/// tree.chmin(.., 3); // [3, 1, 3, 1, 3]
+/// tree.add(..2, 2); // [5, 3, 3, 1, 3]
+/// assert_eq!(tree.query(..), 15);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SegTreeBeats<T> {
+    size: usize,
+    max_depth: u32,
+    nodes: Box<[ChminAddNode<T>]>,
+}
+
+impl<T> SegTreeBeats<T>
+where
+    T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+    T: ConstZero + NumCast,
+{
+    // ===== CONSTRUCTORS =====
+
+    /// Creates a new tree with `size` zero-valued elements.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn new(size: usize) -> Self {
+        Self::from_vec(vec![T::ZERO; size])
+    }
+
+    /// Creates a new tree from a slice of values.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_slice(values: &[T]) -> Self {
+        Self::from_vec(values.to_vec())
+    }
+
+    /// Creates a new tree from a vector of values.
+    ///
+    /// # Time Complexity
+    /// O(n)
+    pub fn from_vec(values: Vec<T>) -> Self {
+        let size = values.len();
+        let max_size = size.next_power_of_two();
+        let max_depth = max_size.trailing_zeros();
+        let padding = ChminAddNode {
+            sum: T::ZERO,
+            max: T::ZERO,
+            second_max: None,
+            max_count: 0,
+            len: 0,
+            add: T::ZERO,
+        };
+        let mut nodes = vec![padding; 2 * max_size];
+
+        for (i, v) in values.into_iter().enumerate() {
+            nodes[max_size + i] = ChminAddNode {
+                sum: v,
+                max: v,
+                second_max: None,
+                max_count: 1,
+                len: 1,
+                add: T::ZERO,
+            };
+        }
+
+        let mut tree = Self {
+            size,
+            max_depth,
+            nodes: nodes.into_boxed_slice(),
+        };
+        for i in (1..max_size).rev() {
+            tree.pull(SegTreeNode(i));
+        }
+        tree
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Returns the sum of the elements in the given range.
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn query<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        if left == right {
+            return T::ZERO;
+        }
+        self.query_node(SegTreeNode(1), self.nodes[1].clone(), left, right)
+    }
+
+    /// Replaces every element `a[i]` in the range with `min(a[i], x)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeBeats;
+    ///
+    /// let mut tree = SegTreeBeats::<i64>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.chmin(1.., 2); // [1, 2, 2, 2]
+    /// assert_eq!(tree.query(..), 7);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// Amortized O(log² n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn chmin<R: RangeBounds<usize>>(&mut self, range: R, x: T) {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        if left == right {
+            return;
+        }
+        self.chmin_node(SegTreeNode(1), left, right, x);
+    }
+
+    /// Adds `x` to every element in the range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use array_range_query::SegTreeBeats;
+    ///
+    /// let mut tree = SegTreeBeats::<i64>::from_vec(vec![1, 2, 3, 4]);
+    /// tree.add(..2, -1); // [0, 1, 3, 4]
+    /// assert_eq!(tree.query(..), 8);
+    /// ```
+    ///
+    /// # Time Complexity
+    /// O(log n)
+    ///
+    /// # Panics
+    /// Panics if the range is invalid or out of bounds.
+    pub fn add<R: RangeBounds<usize>>(&mut self, range: R, x: T) {
+        let (left, right) = utils::parse_range(range, self.size);
+        utils::validate_range(left, right, self.size);
+        if left == right {
+            return;
+        }
+        self.add_node(SegTreeNode(1), left, right, x);
+    }
+
+    // ===== PRIVATE HELPER METHODS =====
+
+    fn count_as_t(count: usize) -> T {
+        T::from(count).unwrap_or_else(|| panic!("Failed to convert usize to T"))
+    }
+
+    fn apply_add(node: &mut ChminAddNode<T>, x: T) {
+        if node.len == 0 {
+            return;
+        }
+        node.sum = node.sum + x * Self::count_as_t(node.len);
+        node.max = node.max + x;
+        node.second_max = node.second_max.map(|v| v + x);
+        node.add = node.add + x;
+    }
+
+    /// Lowers the maximum of a node to `x`, assuming `second_max < x`.
+    fn apply_chmin(node: &mut ChminAddNode<T>, x: T) {
+        if node.len > 0 && node.max > x {
+            node.sum = node.sum - (node.max - x) * Self::count_as_t(node.max_count);
+            node.max = x;
+        }
+    }
+
+    fn chmin_node(&mut self, node: SegTreeNode, left: usize, right: usize, x: T) {
+        let (node_left, node_right) = node.node_bounds(self.max_depth);
+        let state = &self.nodes[node.0];
+        if right <= node_left || node_right <= left || state.len == 0 || state.max <= x {
+            return;
+        }
+        if left <= node_left && node_right <= right && state.second_max.is_none_or(|v| v < x) {
+            Self::apply_chmin(&mut self.nodes[node.0], x);
+            return;
+        }
+
+        self.push(node);
+        self.chmin_node(node.left_child(), left, right, x);
+        self.chmin_node(node.right_child(), left, right, x);
+        self.pull(node);
+    }
+
+    fn add_node(&mut self, node: SegTreeNode, left: usize, right: usize, x: T) {
+        let (node_left, node_right) = node.node_bounds(self.max_depth);
+        if right <= node_left || node_right <= left {
+            return;
+        }
+        if left <= node_left && node_right <= right {
+            Self::apply_add(&mut self.nodes[node.0], x);
+            return;
+        }
+
+        self.push(node);
+        self.add_node(node.left_child(), left, right, x);
+        self.add_node(node.right_child(), left, right, x);
+        self.pull(node);
+    }
+
+    /// Sums `[left, right)` inside `node`, whose effective state is `state`.
+    fn query_node(
+        &self,
+        node: SegTreeNode,
+        state: ChminAddNode<T>,
+        left: usize,
+        right: usize,
+    ) -> T {
+        let (node_left, node_right) = node.node_bounds(self.max_depth);
+        if right <= node_left || node_right <= left {
+            return T::ZERO;
+        }
+        if left <= node_left && node_right <= right {
+            return state.sum;
+        }
+
+        let mut left_state = self.nodes[node.left_child().0].clone();
+        let mut right_state = self.nodes[node.right_child().0].clone();
+        for child in [&mut left_state, &mut right_state] {
+            Self::apply_add(child, state.add);
+            Self::apply_chmin(child, state.max);
+        }
+        self.query_node(node.left_child(), left_state, left, right)
+            + self.query_node(node.right_child(), right_state, left, right)
+    }
+
+    fn push(&mut self, node: SegTreeNode) {
+        let add = core::mem::replace(&mut self.nodes[node.0].add, T::ZERO);
+        let max = self.nodes[node.0].max;
+        for child in [node.left_child(), node.right_child()] {
+            Self::apply_add(&mut self.nodes[child.0], add);
+            Self::apply_chmin(&mut self.nodes[child.0], max);
+        }
+    }
+
+    fn pull(&mut self, node: SegTreeNode) {
+        let l = &self.nodes[node.left_child().0];
+        let r = &self.nodes[node.right_child().0];
+        // Pending adds belong to the children, so the parent starts clean
+        let merged = if r.len == 0 {
+            ChminAddNode {
+                add: T::ZERO,
+                ..l.clone()
+            }
+        } else if l.len == 0 {
+            ChminAddNode {
+                add: T::ZERO,
+                ..r.clone()
+            }
+        } else {
+            let (max, second_max, max_count) = match l.max.cmp(&r.max) {
+                core::cmp::Ordering::Equal => (
+                    l.max,
+                    Self::max_option(l.second_max, r.second_max),
+                    l.max_count + r.max_count,
+                ),
+                core::cmp::Ordering::Greater => (
+                    l.max,
+                    Self::max_option(l.second_max, Some(r.max)),
+                    l.max_count,
+                ),
+                core::cmp::Ordering::Less => (
+                    r.max,
+                    Self::max_option(r.second_max, Some(l.max)),
+                    r.max_count,
+                ),
+            };
+            ChminAddNode {
+                sum: l.sum + r.sum,
+                max,
+                second_max,
+                max_count,
+                len: l.len + r.len,
+                add: T::ZERO,
+            }
+        };
+        self.nodes[node.0] = merged;
+    }
+
+    fn max_option(a: Option<T>, b: Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_chmax_basic() {
+        let mut tree = SegTreeBeatsChmaxSum::<i64>::from_vec(vec![5, 1, 7, 3, 2, 6]);
+        assert_eq!(tree.query(..), 24);
+
+        tree.chmax(1..5, 4); // [5, 4, 7, 4, 4, 6]
+        assert_eq!(tree.query(..), 30);
+        assert_eq!(tree.query(1..2), 4);
+        assert_eq!(tree.query(2..4), 11);
+
+        // Raising below every value is a no-op
+        tree.chmax(.., 0);
+        assert_eq!(tree.query(..), 30);
+    }
+
+    #[test]
+    fn test_chmax_partial_node() {
+        // Only some elements inside each node are below the threshold
+        let mut tree = SegTreeBeatsChmaxSum::<i32>::from_vec(vec![1, 10, 2, 20, 3, 30, 4, 40]);
+        tree.chmax(.., 5);
+        assert_eq!(tree.query(..), 5 + 10 + 5 + 20 + 5 + 30 + 5 + 40);
+        tree.chmax(2..7, 25);
+        assert_eq!(tree.query(..), 5 + 10 + 25 + 25 + 25 + 30 + 25 + 40);
+        assert_eq!(tree.query(5..6), 30);
+    }
+
+    #[test]
+    fn test_chmax_random_against_brute_force() {
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let size = rng.random_range(1..40);
+            let mut expected: Vec<i64> = (0..size).map(|_| rng.random_range(-50..50)).collect();
+            let mut tree = SegTreeBeatsChmaxSum::<i64>::from_slice(&expected);
+
+            for _ in 0..200 {
+                let l = rng.random_range(0..=size);
+                let r = rng.random_range(l..=size);
+                if rng.random_bool(0.5) {
+                    let x = rng.random_range(-60..60);
+                    tree.chmax(l..r, x);
+                    expected[l..r].iter_mut().for_each(|v| *v = (*v).max(x));
+                } else {
+                    assert_eq!(tree.query(l..r), expected[l..r].iter().sum::<i64>());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_chmax_new_and_edge_cases() {
+        let mut tree = SegTreeBeatsChmaxSum::<i32>::new(5);
+        assert_eq!(tree.query(..), 0);
+        tree.chmax(3..3, 100);
+        assert_eq!(tree.query(..), 0);
+        tree.chmax(..=1, 3);
+        assert_eq!(tree.query(..), 6);
+        tree.chmax(4.., i32::MAX - 10);
+        assert_eq!(tree.query(4..), i32::MAX - 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_chmax_out_of_bounds() {
+        let mut tree = SegTreeBeatsChmaxSum::<i32>::new(5);
+        tree.chmax(..6, 1);
+    }
+
+    #[test]
+    fn test_chmin_add_basic() {
+        let mut tree = SegTreeBeats::<i64>::from_vec(vec![5, 1, 7, 3, 2, 6]);
+        assert_eq!(tree.query(..), 24);
+
+        tree.chmin(1..5, 4); // [5, 1, 4, 3, 2, 6]
+        assert_eq!(tree.query(..), 21);
+        assert_eq!(tree.query(2..3), 4);
+
+        tree.add(..3, 10); // [15, 11, 14, 3, 2, 6]
+        assert_eq!(tree.query(..), 51);
+
+        tree.chmin(.., 5); // [5, 5, 5, 3, 2, 5]
+        assert_eq!(tree.query(..), 25);
+        assert_eq!(tree.query(3..5), 5);
+    }
+
+    #[test]
+    fn test_chmin_add_random_against_brute_force() {
+        let mut rng = rand::rng();
+        for _ in 0..10 {
+            let size = rng.random_range(1..60);
+            let mut expected: Vec<i64> = (0..size).map(|_| rng.random_range(-50..50)).collect();
+            let mut tree = SegTreeBeats::<i64>::from_slice(&expected);
+
+            for _ in 0..1000 {
+                let l = rng.random_range(0..=size);
+                let r = rng.random_range(l..=size);
+                match rng.random_range(0..3) {
+                    0 => {
+                        let x = rng.random_range(-60..60);
+                        tree.chmin(l..r, x);
+                        expected[l..r].iter_mut().for_each(|v| *v = (*v).min(x));
+                    }
+                    1 => {
+                        let x = rng.random_range(-20..20);
+                        tree.add(l..r, x);
+                        expected[l..r].iter_mut().for_each(|v| *v += x);
+                    }
+                    _ => assert_eq!(tree.query(l..r), expected[l..r].iter().sum::<i64>()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_panic_chmin_out_of_bounds() {
+        let mut tree = SegTreeBeats::<i32>::new(5);
+        tree.chmin(2..6, 1);
+    }
+}
@@ -15,6 +15,8 @@
 //!
 //! Each node represents a range [left, right) in the underlying array.
 
+use core::ops::Range;
+
 /// A node in a power-of-two layout segment tree.
 ///
 /// This struct wraps a `usize` index representing a node's position in the tree.
@@ -32,8 +34,24 @@
 /// assert_eq!(left_child.0, 2);
 /// assert_eq!(right_child.0, 3);
 /// ```
+///
+/// # Ordering
+///
+/// Nodes are ordered by the position of the range they cover, not by raw
+/// index: first by left bound, then by depth, so an ancestor sorts before the
+/// descendants that share its left bound. This is the order in which a
+/// left-to-right traversal meets the nodes, and it does not depend on the
+/// tree's `max_depth`.
+///
+/// ```rust
+/// use array_range_query::SegTreeNode;
+///
+/// // Node 3 covers the right half, node 4 the first quarter.
+/// assert!(SegTreeNode(4) < SegTreeNode(3));
+/// assert!(SegTreeNode(2) < SegTreeNode(4));
+/// ```
 #[repr(transparent)]
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct SegTreeNode(pub usize);
 
 impl SegTreeNode {
@@ -82,6 +100,27 @@ impl SegTreeNode {
         SegTreeNode(self.0 ^ 1)
     }
 
+    /// Returns an iterator over the strict ancestors of this node, from its
+    /// parent up to and including the root.
+    ///
+    /// The node itself is not yielded, so the root yields nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use array_range_query::SegTreeNode;
+    ///
+    /// let path: Vec<usize> = SegTreeNode(8).ancestors().map(|n| n.0).collect();
+    /// assert_eq!(path, vec![4, 2, 1]);
+    /// assert_eq!(SegTreeNode(1).ancestors().count(), 0);
+    /// ```
+    pub fn ancestors(&self) -> impl Iterator<Item = SegTreeNode> {
+        core::iter::successors(Some(*self), |node| {
+            (!node.is_root()).then_some(SegTreeNode(node.0 / 2))
+        })
+        .skip(1)
+    }
+
     // ===== PROPERTIES =====
 
     /// Returns true if this is the root node.
@@ -120,6 +159,12 @@ impl SegTreeNode {
         self.0.ilog2()
     }
 
+    /// Alias for [`depth`](Self::depth).
+    #[inline]
+    pub fn level(&self) -> u32 {
+        self.depth()
+    }
+
     /// Returns true if this node is a leaf (at maximum depth).
     ///
     /// # Parameters
@@ -162,6 +207,12 @@ impl SegTreeNode {
         1 << (max_depth - self.depth())
     }
 
+    /// Alias for [`size`](Self::size).
+    #[inline]
+    pub fn node_size(&self, max_depth: u32) -> usize {
+        self.size(max_depth)
+    }
+
     /// Returns the left boundary of the range this node represents.
     ///
     /// # Parameters
@@ -182,6 +233,12 @@ impl SegTreeNode {
         pos * (1 << (max_depth - depth))
     }
 
+    /// Alias for [`left_bound`](Self::left_bound).
+    #[inline]
+    pub fn node_left_bound(&self, max_depth: u32) -> usize {
+        self.left_bound(max_depth)
+    }
+
     /// Returns the right boundary of the range this node represents.
     ///
     /// # Parameters
@@ -202,6 +259,12 @@ impl SegTreeNode {
         (pos + 1) * (1 << (max_depth - depth))
     }
 
+    /// Alias for [`right_bound`](Self::right_bound).
+    #[inline]
+    pub fn node_right_bound(&self, max_depth: u32) -> usize {
+        self.right_bound(max_depth)
+    }
+
     /// Returns the midpoint of the range this node represents.
     #[inline]
     pub fn mid(&self, max_depth: u32) -> usize {
@@ -232,6 +295,85 @@ impl SegTreeNode {
         (pos * range, (pos + 1) * range)
     }
 
+    /// Returns the logical leaf indices `left..right` this node covers.
+    ///
+    /// Same as [`node_bounds`](Self::node_bounds), as a `Range`. In a tree
+    /// whose length is not a power of two, indices at or past the length are
+    /// padding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use array_range_query::SegTreeNode;
+    ///
+    /// assert_eq!(SegTreeNode(3).leaf_range(3), 4..8);
+    /// ```
+    #[inline]
+    pub fn leaf_range(&self, max_depth: u32) -> Range<usize> {
+        let (left, right) = self.node_bounds(max_depth);
+        left..right
+    }
+
+    /// Returns an iterator over the logical leaf indices this node covers,
+    /// in increasing order.
+    ///
+    /// Handy for attaching per-leaf auxiliary data to a node or checking
+    /// which elements it summarizes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use array_range_query::SegTreeNode;
+    ///
+    /// let leaves: Vec<usize> = SegTreeNode(2).leaves(3).collect();
+    /// assert_eq!(leaves, vec![0, 1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn leaves(&self, max_depth: u32) -> impl Iterator<Item = usize> {
+        self.leaf_range(max_depth)
+    }
+
+    /// Returns the number of leaves this node covers, the same as
+    /// [`size`](Self::size).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use array_range_query::SegTreeNode;
+    ///
+    /// assert_eq!(SegTreeNode(2).leaf_count(3), 4);
+    /// assert_eq!(SegTreeNode(9).leaf_count(3), 1);
+    /// ```
+    #[inline]
+    pub fn leaf_count(&self, max_depth: u32) -> usize {
+        self.size(max_depth)
+    }
+
+    /// Returns the node whose range is exactly `[left, left + len)`, or
+    /// `None` if no node covers that range.
+    ///
+    /// This is the inverse of [`node_bounds`](Self::node_bounds): such a node
+    /// exists only when `len` is a power of two, `left` is a multiple of
+    /// `len`, and the range lies within the `2^max_depth` leaves.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use array_range_query::SegTreeNode;
+    ///
+    /// assert_eq!(SegTreeNode::covering(4, 4, 3), Some(SegTreeNode(3)));
+    /// assert_eq!(SegTreeNode::covering(2, 4, 3), None); // misaligned
+    /// assert_eq!(SegTreeNode::covering(0, 3, 3), None); // not a power of two
+    /// ```
+    pub fn covering(left: usize, len: usize, max_depth: u32) -> Option<SegTreeNode> {
+        let leaves = 1usize.checked_shl(max_depth)?;
+        if !len.is_power_of_two() || len > leaves || left & (len - 1) != 0 || left > leaves - len {
+            return None;
+        }
+        let depth = max_depth - len.trailing_zeros();
+        Some(SegTreeNode((1 << depth) + left / len))
+    }
+
     // ===== LCA HELPERS =====
 
     /// Finds the Lowest Common Ancestor (LCA) of two nodes at the same depth.
@@ -298,6 +440,25 @@ impl SegTreeNode {
     }
 }
 
+impl PartialOrd for SegTreeNode {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SegTreeNode {
+    /// Compares by covered left bound, then by depth.
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // Bring both left bounds to the deeper of the two levels, where they
+        // are exact positions that can be compared directly.
+        let (da, db) = (self.depth(), other.depth());
+        let depth = da.max(db);
+        let left_a = (self.0 - (1 << da)) << (depth - da);
+        let left_b = (other.0 - (1 << db)) << (depth - db);
+        left_a.cmp(&left_b).then(da.cmp(&db))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::SegTreeNode;
@@ -335,6 +496,18 @@ mod tests {
         assert!(node3.is_right_child());
     }
 
+    #[test]
+    fn test_ancestors() {
+        let path: Vec<usize> = SegTreeNode(8).ancestors().map(|n| n.0).collect();
+        assert_eq!(path, vec![4, 2, 1]);
+
+        let path: Vec<usize> = SegTreeNode(13).ancestors().map(|n| n.0).collect();
+        assert_eq!(path, vec![6, 3, 1]);
+
+        assert_eq!(SegTreeNode(1).ancestors().next(), None);
+        assert_eq!(SegTreeNode(3).ancestors().count(), 1);
+    }
+
     #[test]
     fn test_depth_calculation() {
         let root = SegTreeNode(1);
@@ -357,6 +530,53 @@ mod tests {
         assert_eq!(root.node_bounds(max_depth), (0, 8));
     }
 
+    #[test]
+    fn test_leaves() {
+        assert_eq!(
+            SegTreeNode(2).leaves(3).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(SegTreeNode(7).leaves(3).collect::<Vec<_>>(), vec![6, 7]);
+        assert_eq!(SegTreeNode(12).leaves(3).collect::<Vec<_>>(), vec![4]);
+
+        // Every node at a given depth partitions the leaves
+        for depth in 0..=3 {
+            let covered: Vec<usize> = (1 << depth..2 << depth)
+                .flat_map(|i| SegTreeNode(i).leaves(3))
+                .collect();
+            assert_eq!(covered, (0..8).collect::<Vec<_>>());
+        }
+        for i in 1..16 {
+            let node = SegTreeNode(i);
+            assert_eq!(node.leaf_count(3), node.leaves(3).count());
+            assert_eq!(node.leaf_range(3).start, node.left_bound(3));
+        }
+    }
+
+    #[test]
+    fn test_covering() {
+        let max_depth = 4;
+        let leaves = 1 << max_depth;
+
+        // Every node is recovered from its own bounds
+        for idx in 1..2 * leaves {
+            let node = SegTreeNode(idx);
+            let (left, right) = node.node_bounds(max_depth);
+            assert_eq!(
+                SegTreeNode::covering(left, right - left, max_depth),
+                Some(node)
+            );
+        }
+
+        // Misaligned, non-power-of-two, empty and out-of-range segments
+        assert_eq!(SegTreeNode::covering(1, 2, max_depth), None);
+        assert_eq!(SegTreeNode::covering(4, 8, max_depth), None);
+        assert_eq!(SegTreeNode::covering(0, 3, max_depth), None);
+        assert_eq!(SegTreeNode::covering(0, 0, max_depth), None);
+        assert_eq!(SegTreeNode::covering(16, 1, max_depth), None);
+        assert_eq!(SegTreeNode::covering(0, 32, max_depth), None);
+    }
+
     #[test]
     fn test_leaf_detection() {
         let root = SegTreeNode(1);
@@ -397,4 +617,28 @@ mod tests {
         assert!(left_binding.0 > 0);
         assert!(right_binding.0 > 0);
     }
+
+    #[test]
+    fn test_ordering_by_range_position() {
+        let mut nodes: Vec<SegTreeNode> = (1..16).map(SegTreeNode).collect();
+        nodes.sort();
+        let order: Vec<usize> = nodes.iter().map(|n| n.0).collect();
+        assert_eq!(
+            order,
+            vec![1, 2, 4, 8, 9, 5, 10, 11, 3, 6, 12, 13, 7, 14, 15]
+        );
+
+        // Left bounds are non-decreasing, ties broken by depth
+        for pair in nodes.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert!(a.left_bound(3) <= b.left_bound(3));
+            if a.left_bound(3) == b.left_bound(3) {
+                assert!(a.depth() < b.depth());
+            }
+        }
+
+        let set: std::collections::BTreeSet<_> = [7, 2, 12, 1].map(SegTreeNode).into();
+        let order: Vec<usize> = set.into_iter().map(|n| n.0).collect();
+        assert_eq!(order, vec![1, 2, 12, 7]);
+    }
 }
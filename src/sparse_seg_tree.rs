@@ -0,0 +1,173 @@
+//! Coordinate-compressed segment tree over a sparse key space.
+//!
+//! [`SparseSegTree`] is built from the set of coordinates that will ever be
+//! updated. They are sorted and deduplicated once, and each coordinate is
+//! mapped to its rank in a dense [`SegTree`]. Queries over raw coordinate
+//! ranges are translated with binary search, so the key space can be as
+//! large as `u64` while memory stays proportional to the number of points.
+//!
+//! # Example
+//!
+//! ```rust
+//! use array_range_query::helpers::SegTreeSumSpec;
+//! use array_range_query::SparseSegTree;
+//!
+//! let mut tree = SparseSegTree::<SegTreeSumSpec<i64>>::new([10, 1_000_000, u64::MAX]);
+//! tree.update(10, 5);
+//! tree.update(u64::MAX, 7);
+//! assert_eq!(tree.query(..), 12);
+//! assert_eq!(tree.query(11..), 7);
+//! assert_eq!(tree.query(0..=10), 5);
+//! ```
+
+use crate::{utils, SegTree, SegTreeSpec};
+use alloc::vec::Vec;
+use core::ops::RangeBounds;
+
+/// A segment tree indexed by arbitrary ordered coordinates, registered up
+/// front and compressed onto a dense [`SegTree`].
+///
+/// Every point starts at `Spec::id()`. Only registered coordinates can be
+/// updated; queries accept any range of coordinates.
+///
+/// # Type Parameters
+///
+/// * `Spec` - A type implementing `SegTreeSpec` that defines the operation and element type
+/// * `K` - The coordinate type, `u64` by default
+pub struct SparseSegTree<Spec: SegTreeSpec, K: Ord = u64> {
+    /// Sorted, deduplicated coordinates; position `i` maps to leaf `i`
+    coords: Vec<K>,
+    /// Dense tree over the compressed indices
+    tree: SegTree<Spec>,
+}
+
+impl<Spec: SegTreeSpec, K: Ord> SparseSegTree<Spec, K> {
+    // ===== CONSTRUCTORS =====
+
+    /// Creates a tree over the given coordinates, all set to `Spec::id()`.
+    ///
+    /// Coordinates may be given in any order and may repeat.
+    ///
+    /// # Time Complexity
+    /// O(m log m) for `m` coordinates
+    pub fn new<I: IntoIterator<Item = K>>(coords: I) -> Self {
+        let mut coords: Vec<K> = coords.into_iter().collect();
+        coords.sort_unstable();
+        coords.dedup();
+        let tree = SegTree::new(coords.len());
+        Self { coords, tree }
+    }
+
+    // ===== PUBLIC INTERFACE =====
+
+    /// Returns the number of distinct registered coordinates.
+    pub fn len(&self) -> usize {
+        self.coords.len()
+    }
+
+    /// Returns `true` if no coordinates are registered.
+    pub fn is_empty(&self) -> bool {
+        self.coords.is_empty()
+    }
+
+    /// Aggregates the values of all registered coordinates inside `range`.
+    ///
+    /// # Time Complexity
+    /// O(log m)
+    ///
+    /// # Panics
+    /// Panics if both bounds are present and the start lies after the end.
+    pub fn query<R: RangeBounds<K>>(&self, range: R) -> Spec::T {
+        let (left, right) = utils::compress_range(&self.coords, range);
+        self.tree.query(left..right)
+    }
+
+    /// Sets the value stored at `coord`.
+    ///
+    /// # Time Complexity
+    /// O(log m)
+    ///
+    /// # Panics
+    /// Panics if `coord` was not registered at construction.
+    pub fn update(&mut self, coord: K, value: Spec::T) {
+        let index = self
+            .coords
+            .binary_search(&coord)
+            .unwrap_or_else(|_| panic!("coordinate was not registered in SparseSegTree"));
+        self.tree.update(index, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::SegTreeSumSpec;
+    use core::ops::Bound;
+    use rand::Rng;
+    use std::collections::BTreeMap;
+
+    type SumSparse = SparseSegTree<SegTreeSumSpec<i64>>;
+
+    #[test]
+    fn test_basic_queries() {
+        let mut tree = SumSparse::new([5, 1, 9, 5, 1 << 40]);
+        assert_eq!(tree.len(), 4);
+
+        tree.update(1, 10);
+        tree.update(9, 3);
+        tree.update(1 << 40, 100);
+
+        assert_eq!(tree.query(..), 113);
+        assert_eq!(tree.query(1..9), 10);
+        assert_eq!(tree.query(1..=9), 13);
+        assert_eq!(tree.query((Bound::Excluded(1), Bound::Unbounded)), 103);
+        assert_eq!(tree.query(2..5), 0);
+        assert_eq!(tree.query(6..6), 0);
+    }
+
+    #[test]
+    fn test_random_against_btreemap() {
+        let mut rng = rand::rng();
+        let mut coords: Vec<u64> = (0..100).map(|_| rng.random()).collect();
+        coords.extend([0, u64::MAX]);
+
+        let mut tree = SumSparse::new(coords.iter().copied());
+        let mut brute: BTreeMap<u64, i64> = BTreeMap::new();
+
+        for _ in 0..2000 {
+            if rng.random_bool(0.5) {
+                let coord = coords[rng.random_range(0..coords.len())];
+                let value = rng.random_range(-1000..1000);
+                tree.update(coord, value);
+                brute.insert(coord, value);
+            } else {
+                let (mut a, mut b): (u64, u64) = (rng.random(), rng.random());
+                if rng.random_bool(0.3) {
+                    a = coords[rng.random_range(0..coords.len())];
+                }
+                if rng.random_bool(0.3) {
+                    b = coords[rng.random_range(0..coords.len())];
+                }
+                let (a, b) = (a.min(b), a.max(b));
+                assert_eq!(tree.query(a..b), brute.range(a..b).map(|(_, v)| v).sum());
+                assert_eq!(tree.query(a..=b), brute.range(a..=b).map(|(_, v)| v).sum());
+                assert_eq!(tree.query(a..), brute.range(a..).map(|(_, v)| v).sum());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not registered")]
+    fn test_panic_update_unregistered() {
+        let mut tree = SumSparse::new([1, 2, 3]);
+        tree.update(4, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_panic_reversed_range() {
+        let tree = SumSparse::new([1, 2, 3]);
+        tree.query(3..1);
+    }
+}
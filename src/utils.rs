@@ -4,20 +4,122 @@
 use core::ops::{Bound, RangeBounds};
 
 /// Converts any `RangeBounds<usize>` into a concrete `[start, end)` tuple.
+///
+/// Inclusive ends and excluded starts are bumped by one, so the largest
+/// supported value for either is `usize::MAX - 1`. Since no tree holds more
+/// than `usize::MAX` elements, this covers every in-bounds range: `..=size - 1`
+/// always parses to `(0, size)`.
+///
+/// Any pair of [`Bound`]s is accepted, e.g. `(Bound::Excluded(a),
+/// Bound::Excluded(b))` parses to `(a + 1, b)`. Like slice indexing, a pair
+/// that crosses, such as `(Excluded(3), Excluded(3))`, is rejected by
+/// [`validate_range`] rather than treated as empty.
+///
+/// # Panics
+/// Panics with an "Invalid range" message if an inclusive end or exclusive
+/// start equals `usize::MAX`, since the half-open bound would overflow.
 pub(crate) fn parse_range<R: RangeBounds<usize>>(range: R, size: usize) -> (usize, usize) {
+    // Only an inclusive end or exclusive start of `usize::MAX` can overflow
+    checked_bounds(range, size).unwrap_or_else(|| {
+        panic!(
+            "Invalid range: bound {} overflows usize, size is {}",
+            usize::MAX,
+            size
+        )
+    })
+}
+
+/// Non-panicking counterpart of [`parse_range`] followed by [`validate_range`].
+///
+/// Returns `None` if a bound overflows, `left > right`, or `right > size`.
+pub(crate) fn try_parse_range<R: RangeBounds<usize>>(
+    range: R,
+    size: usize,
+) -> Option<(usize, usize)> {
+    let (start, end) = checked_bounds(range, size)?;
+    (start <= end && end <= size).then_some((start, end))
+}
+
+/// Converts the bounds to a half-open `[start, end)` pair without validating
+/// it, or returns `None` if bumping an inclusive end or exclusive start
+/// overflows.
+fn checked_bounds<R: RangeBounds<usize>>(range: R, size: usize) -> Option<(usize, usize)> {
     let start = match range.start_bound() {
         Bound::Included(&s) => s,
-        Bound::Excluded(&s) => s + 1,
+        Bound::Excluded(&s) => s.checked_add(1)?,
         Bound::Unbounded => 0,
     };
     let end = match range.end_bound() {
-        Bound::Included(&e) => e + 1,
+        Bound::Included(&e) => e.checked_add(1)?,
         Bound::Excluded(&e) => e,
         Bound::Unbounded => size,
     };
+    Some((start, end))
+}
+
+/// Converts a `(start, len)` pair into a validated `[start, start + len)`.
+///
+/// # Panics
+/// Panics with an "Invalid range" message if `start + len` overflows or
+/// exceeds `size`.
+pub(crate) fn parse_len_range(start: usize, len: usize, size: usize) -> (usize, usize) {
+    let end = start.checked_add(len).unwrap_or_else(|| {
+        panic!(
+            "Invalid range: start {} plus length {} overflows usize, size is {}",
+            start, len, size
+        )
+    });
+    validate_range(start, end, size);
     (start, end)
 }
 
+/// Resolves a Python-style index, where negative values count back from
+/// `size` (`-1` is `size - 1`), into a position in `0..=size`.
+///
+/// # Panics
+/// Panics with an "Invalid range" message if the resolved position falls
+/// outside `0..=size`.
+pub(crate) fn resolve_rel_index(index: isize, size: usize) -> usize {
+    let resolved = if index >= 0 {
+        Some(index.unsigned_abs())
+    } else {
+        size.checked_sub(index.unsigned_abs())
+    };
+    match resolved {
+        Some(position) if position <= size => position,
+        _ => panic!(
+            "Invalid range: relative index {} is out of bounds, size is {}",
+            index, size
+        ),
+    }
+}
+
+/// Maps a range of raw coordinates onto `[start, end)` positions in the
+/// sorted, deduplicated `coords`.
+///
+/// # Panics
+/// Panics if both bounds are present and the start lies after the end.
+pub(crate) fn compress_range<K: Ord, R: RangeBounds<K>>(coords: &[K], range: R) -> (usize, usize) {
+    if let (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) =
+        (range.start_bound(), range.end_bound())
+    {
+        assert!(s <= e, "Invalid range: start coordinate is after the end");
+    }
+
+    let start = match range.start_bound() {
+        Bound::Included(s) => coords.partition_point(|c| c < s),
+        Bound::Excluded(s) => coords.partition_point(|c| c <= s),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(e) => coords.partition_point(|c| c <= e),
+        Bound::Excluded(e) => coords.partition_point(|c| c < e),
+        Bound::Unbounded => coords.len(),
+    };
+    // Bounds such as `(Excluded(x), Excluded(x))` cross; treat them as empty
+    (start, end.max(start))
+}
+
 /// Validates that a range `[left, right)` is within bounds.
 ///
 /// # Panics
@@ -31,3 +133,127 @@ pub(crate) fn validate_range(left: usize, right: usize, size: usize) {
         size
     );
 }
+
+/// Recursively prints the present values of a 1-based tree, one node per
+/// line, indented by depth and annotated with the range `[l, r)` it covers.
+#[cfg(feature = "std")]
+pub(crate) fn print_tree_option<T: core::fmt::Display>(
+    f: &mut std::fmt::Formatter<'_>,
+    tree: &[&Option<T>],
+    index: usize,
+    depth: usize,
+    l: usize,
+    r: usize,
+) -> std::fmt::Result {
+    if index >= tree.len() {
+        return Ok(());
+    }
+
+    if let Some(value) = &tree[index] {
+        for _ in 0..depth {
+            write!(f, "  ")?;
+        }
+        writeln!(f, "{} (Index: {}, Covers [{}, {}))", value, index, l, r)?;
+    }
+
+    if index * 2 + 1 < tree.len() {
+        print_tree_option(f, tree, index * 2, depth + 1, l, (l + r) / 2)?;
+        print_tree_option(f, tree, index * 2 + 1, depth + 1, (l + r) / 2, r)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::ops::Bound::{Excluded, Unbounded};
+
+    #[test]
+    fn test_parse_range_bounds() {
+        assert_eq!(parse_range(.., 5), (0, 5));
+        assert_eq!(parse_range(1..=3, 5), (1, 4));
+        assert_eq!(parse_range((Excluded(1), Unbounded), 5), (2, 5));
+        assert_eq!(parse_range(..=usize::MAX - 1, 5), (0, usize::MAX));
+    }
+
+    /// Every bound over `0..=size` plus `Unbounded`.
+    fn all_bounds(size: usize) -> Vec<Bound<usize>> {
+        let mut bounds = vec![Unbounded];
+        for i in 0..=size {
+            bounds.push(Bound::Included(i));
+            bounds.push(Excluded(i));
+        }
+        bounds
+    }
+
+    /// Half-open `[start, end)` intended by a pair of bounds.
+    fn expected_range(start: Bound<usize>, end: Bound<usize>, size: usize) -> (usize, usize) {
+        let start = match start {
+            Bound::Included(s) => s,
+            Excluded(s) => s + 1,
+            Unbounded => 0,
+        };
+        let end = match end {
+            Bound::Included(e) => e + 1,
+            Excluded(e) => e,
+            Unbounded => size,
+        };
+        (start, end)
+    }
+
+    #[test]
+    fn test_parse_range_every_bound_pair() {
+        let size = 8;
+        for &start in &all_bounds(size) {
+            for &end in &all_bounds(size) {
+                let expected = expected_range(start, end, size);
+                assert_eq!(parse_range((start, end), size), expected);
+
+                let valid = expected.0 <= expected.1 && expected.1 <= size;
+                assert_eq!(
+                    try_parse_range((start, end), size),
+                    valid.then_some(expected),
+                    "bounds ({:?}, {:?})",
+                    start,
+                    end
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_range_inclusive_end_at_last_index() {
+        // Sizes near the platform maximum, checked on the arithmetic alone
+        for size in [1, 5, usize::MAX / 2, usize::MAX - 1, usize::MAX] {
+            let full = parse_range(0..size, size);
+            assert_eq!(full, (0, size));
+            assert_eq!(parse_range(..=size - 1, size), full);
+            assert_eq!(parse_range(0..=size - 1, size), full);
+            assert_eq!(parse_range(size - 1..=size - 1, size), (size - 1, size));
+            assert_eq!(try_parse_range(..=size - 1, size), Some(full));
+            validate_range(full.0, full.1, size);
+        }
+    }
+
+    #[test]
+    fn test_try_parse_range() {
+        assert_eq!(try_parse_range(1..=3, 5), Some((1, 4)));
+        assert_eq!(try_parse_range(.., 5), Some((0, 5)));
+        assert_eq!(try_parse_range(..6, 5), None);
+        assert_eq!(try_parse_range(..=usize::MAX, 5), None);
+        assert_eq!(try_parse_range((Excluded(usize::MAX), Unbounded), 5), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_parse_range_inclusive_end_overflow() {
+        parse_range(..=usize::MAX, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn test_parse_range_excluded_start_overflow() {
+        parse_range((Excluded(usize::MAX), Unbounded), 5);
+    }
+}
@@ -0,0 +1,29 @@
+//! Builds the trees from a `#![no_std]` crate that only relies on `alloc`.
+//!
+//! Run with `cargo test --no-default-features --test test_no_std` to check
+//! the `alloc`-only core.
+
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod no_std_usage {
+    use alloc::vec;
+    use array_range_query::{LazySegTreeAddSum, SegTreeSum};
+
+    #[test]
+    fn test_seg_tree_sum_without_std() {
+        let mut tree = SegTreeSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+        assert_eq!(tree.query(1..4), 9);
+        tree.update(2, 10);
+        assert_eq!(tree.query(..), 22);
+    }
+
+    #[test]
+    fn test_lazy_seg_tree_add_sum_without_std() {
+        let mut tree = LazySegTreeAddSum::<i32>::from_vec(vec![1, 2, 3, 4, 5]);
+        tree.update(1..4, 10);
+        assert_eq!(tree.query(..), 45);
+    }
+}
@@ -168,6 +168,21 @@ mod comprehensive_test_lazy_seg_tree_node {
         }
     }
 
+    #[test]
+    fn test_alias_methods_match_originals() {
+        let max_depth = 4;
+        for idx in 1..(1 << (max_depth + 1)) {
+            let node = SegTreeNode(idx);
+            assert_eq!(node.level(), node.depth());
+            assert_eq!(node.node_left_bound(max_depth), node.left_bound(max_depth));
+            assert_eq!(
+                node.node_right_bound(max_depth),
+                node.right_bound(max_depth)
+            );
+            assert_eq!(node.node_size(max_depth), node.size(max_depth));
+        }
+    }
+
     #[test]
     fn test_leaf_detection() {
         let max_depth = 3;